@@ -2,8 +2,8 @@ use anchor_lang::prelude::*;
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Validator count cannot exceed 12")]
-    ValidatorOver12,
+    #[msg("Validator count exceeds the maximum")]
+    ValidatorSetFull,
     #[msg("Number of signatures is less than threshold")]
     SignaturesLess,
     #[msg("Invalid threshold")]
@@ -22,4 +22,80 @@ pub enum ErrorCode {
     DeserializationError,
     #[msg("Receiver does not match")]
     ReceiverMismatch,
+    #[msg("Pending admin is invalid")]
+    InvalidPendingAdmin,
+    #[msg("No pending admin or signer is not the pending admin")]
+    NoPendingAdmin,
+    #[msg("Bridge is paused")]
+    BridgePaused,
+    #[msg("Outbound messages to this chain are paused")]
+    ChainOutboundPaused,
+    #[msg("Inbound messages from this chain are paused")]
+    ChainInboundPaused,
+    #[msg("Genesis validator set is already activated")]
+    ValidatorSetSealed,
+    #[msg("Validator weight must be positive")]
+    InvalidWeight,
+    #[msg("Validator not found")]
+    ValidatorNotFound,
+    #[msg("Aggregate public key is invalid")]
+    InvalidAggregateKey,
+    #[msg("Aggregate public key does not match the registered key")]
+    AggregateKeyMismatch,
+    #[msg("Ordered channel only accepts the next nonce")]
+    NonceOutOfOrder,
+    #[msg("Message is not addressed to the caller program")]
+    CallerProgramMismatch,
+    #[msg("Message is already executed")]
+    MessageAlreadyExecuted,
+    #[msg("Message was not delivered by bridge-core")]
+    DeliveryNotAuthorized,
+    #[msg("App name, chains or mtypes exceed the registry limits")]
+    AppRegistryLimitExceeded,
+    #[msg("App is disabled")]
+    AppDisabled,
+    #[msg("App may not send to this chain")]
+    AppChainNotAllowed,
+    #[msg("App may not send this message type")]
+    AppMtypeNotAllowed,
+    #[msg("Bridge fee overflows")]
+    FeeOverflow,
+    #[msg("Fee mint is not accepted")]
+    FeeMintDisabled,
+    #[msg("Fee split must add up to 10000 bps")]
+    InvalidFeeSplit,
+    #[msg("Signer may not claim the rewards of this validator")]
+    ValidatorClaimUnauthorized,
+    #[msg("Outbound Merkle tree is full")]
+    MerkleTreeFull,
+    #[msg("Root was posted for another source chain")]
+    RootChainMismatch,
+    #[msg("Invalid Merkle inclusion proof")]
+    InvalidMerkleProof,
+    #[msg("Source chain is not in optimistic verification mode")]
+    ChainNotOptimistic,
+    #[msg("Attestation bond below the minimum of the chain")]
+    BondTooLow,
+    #[msg("Attestation was cancelled by a fraud claim")]
+    AttestationCancelled,
+    #[msg("Attestation is still in its challenge period")]
+    ChallengePeriodActive,
+    #[msg("Challenge period of the attestation is over")]
+    ChallengePeriodOver,
+    #[msg("An enabled fee mint needs a nonzero tokens_per_sol")]
+    InvalidFeeMintRate,
+    #[msg("Root was posted by a retired validator epoch")]
+    RootEpochRetired,
+    #[msg("Leaf index beyond the message count of the root")]
+    LeafIndexOutOfRange,
+    #[msg("Challenge period below MIN_CHALLENGE_PERIOD")]
+    ChallengePeriodTooShort,
+    #[msg("Minimum attestation bond must be nonzero")]
+    MinBondZero,
+    #[msg("Precompiled signatures are not accepted in VerifyMode::Aggregate")]
+    PrecompiledInAggregateMode,
+    #[msg("Ordered channels are confirmed directly, not through pending messages")]
+    OrderedChannelPending,
+    #[msg("Challenge period end overflows")]
+    ChallengeEndOverflow,
 }
\ No newline at end of file
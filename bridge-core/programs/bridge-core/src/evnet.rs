@@ -1,17 +1,191 @@
-use crate::{state::config::MsgHeader, Chain};
+use crate::{
+    state::{
+        config::{ChainVerifyMode, DeliveryMode, MsgHeader, ValidatorShare, VerifyMode},
+        validator::{Quorum, Validator},
+    },
+    Chain,
+};
 use anchor_lang::prelude::*;
 
 #[event]
 pub struct SendMessage {
+    // committed in the OutboundRing of the channel
+    pub message_hash: [u8; 32],
+    // index of message_hash in the OutboundTree of the destination chain
+    pub leaf_index: u64,
     pub header: MsgHeader,
     pub body: Vec<u8>,
     pub fee: u64,
+    // mint the bridge fee was paid in, None for lamports
+    pub fee_mint: Option<Pubkey>,
+    // charged by bridge-core for the message, in lamports or fee_mint units
+    pub bridge_fee: u64,
 }
 
 #[event]
 pub struct ConfirmMessage {
+    pub message_hash: [u8; 32],
     pub executor: Pubkey,
     pub from_chain: Chain,
     pub nonce: u64,
     pub mbody: Vec<u8>,
 }
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminAccepted {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminProposalCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct GuardianChanged {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct BridgePauseChanged {
+    pub authority: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct ChainPauseChanged {
+    pub authority: Pubkey,
+    pub chain: Chain,
+    pub outbound_paused: bool,
+    pub inbound_paused: bool,
+}
+
+#[event]
+pub struct ValidatorSetRotated {
+    pub epoch: u64,
+    pub validators: Vec<Validator>,
+    pub quorum: Quorum,
+    pub aggregate_pk: [u8; 33],
+}
+
+#[event]
+pub struct VerifyModeChanged {
+    pub mode: VerifyMode,
+    // of the active validator set
+    pub aggregate_pk: [u8; 33],
+}
+
+#[event]
+pub struct ChannelInitialized {
+    pub from_chain: Chain,
+    pub from_addr: [u8; 32],
+    pub caller_program: Pubkey,
+    pub delivery_mode: DeliveryMode,
+}
+
+#[event]
+pub struct MessageVerified {
+    pub message_hash: [u8; 32],
+    pub verifier: Pubkey,
+    pub from_chain: Chain,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct RootPosted {
+    pub from_chain: Chain,
+    pub root: [u8; 32],
+    pub count: u64,
+    pub epoch: u64,
+    pub poster: Pubkey,
+}
+
+#[event]
+pub struct ChainVerificationChanged {
+    pub chain: Chain,
+    pub mode: ChainVerifyMode,
+    pub challenge_period: i64,
+    pub min_bond: u64,
+}
+
+#[event]
+pub struct AttestationPosted {
+    pub from_chain: Chain,
+    pub root: [u8; 32],
+    pub count: u64,
+    pub attester: Pubkey,
+    pub bond: u64,
+    pub challenge_ends_at: i64,
+}
+
+#[event]
+pub struct AttestationChallenged {
+    pub from_chain: Chain,
+    pub root: [u8; 32],
+    pub attester: Pubkey,
+    pub challenger: Pubkey,
+    pub slashed: u64,
+}
+
+#[event]
+pub struct MessageExecutionFailed {
+    pub message_hash: [u8; 32],
+    pub executor: Pubkey,
+    pub attempts: u32,
+}
+
+/// Emitted when an app is registered or its registration is updated
+#[event]
+pub struct AppRegistered {
+    pub program: Pubkey,
+    pub name: String,
+    pub enabled: bool,
+    pub to_chains: Vec<Chain>,
+    pub mtypes: Vec<u8>,
+    pub fee_override: Option<u64>,
+}
+
+#[event]
+pub struct AppRemoved {
+    pub program: Pubkey,
+}
+
+#[event]
+pub struct ChainFeeChanged {
+    pub chain: Chain,
+    pub base_fee: u64,
+    pub byte_fee: u64,
+}
+
+#[event]
+pub struct FeeMintChanged {
+    pub mint: Pubkey,
+    pub tokens_per_sol: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct FeeSplitChanged {
+    pub treasury: Pubkey,
+    pub treasury_bps: u16,
+    pub validator_bps: u16,
+    pub relayer_bps: u16,
+    pub validator_share: ValidatorShare,
+    pub reward_per_message: u64,
+}
+
+#[event]
+pub struct FeeClaimed {
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
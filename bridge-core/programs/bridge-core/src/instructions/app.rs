@@ -0,0 +1,104 @@
+use crate::{
+    evnet::{AppRegistered, AppRemoved},
+    state::config::{AppRegistration, Chain, ConfigInfo},
+};
+use anchor_lang::prelude::*;
+
+/// Allow the `program` account to send messages of `mtypes` to `to_chains`
+pub fn register_app(
+    ctx: Context<RegisterApp>,
+    name: String,
+    to_chains: Vec<Chain>,
+    mtypes: Vec<u8>,
+    fee_override: Option<u64>,
+) -> Result<()> {
+    let program = ctx.accounts.program.key();
+    let app_registration = &mut ctx.accounts.app_registration;
+    app_registration.program = program;
+    app_registration.enabled = true;
+    app_registration.name = name;
+    app_registration.to_chains = to_chains;
+    app_registration.mtypes = mtypes;
+    app_registration.fee_override = fee_override;
+    app_registration.check_limits()?;
+
+    emit!(AppRegistered {
+        program,
+        name: app_registration.name.clone(),
+        enabled: true,
+        to_chains: app_registration.to_chains.clone(),
+        mtypes: app_registration.mtypes.clone(),
+        fee_override,
+    });
+    Ok(())
+}
+
+/// Replace the registration of an app, `enabled = false` suspends its sends
+pub fn update_app(
+    ctx: Context<AppConf>,
+    program: Pubkey,
+    name: String,
+    enabled: bool,
+    to_chains: Vec<Chain>,
+    mtypes: Vec<u8>,
+    fee_override: Option<u64>,
+) -> Result<()> {
+    let app_registration = &mut ctx.accounts.app_registration;
+    app_registration.enabled = enabled;
+    app_registration.name = name;
+    app_registration.to_chains = to_chains;
+    app_registration.mtypes = mtypes;
+    app_registration.fee_override = fee_override;
+    app_registration.check_limits()?;
+
+    emit!(AppRegistered {
+        program,
+        name: app_registration.name.clone(),
+        enabled,
+        to_chains: app_registration.to_chains.clone(),
+        mtypes: app_registration.mtypes.clone(),
+        fee_override,
+    });
+    Ok(())
+}
+
+pub fn remove_app(_ctx: Context<RemoveApp>, program: Pubkey) -> Result<()> {
+    emit!(AppRemoved { program });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterApp<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK:
+    #[account(executable)]
+    pub program: AccountInfo<'info>,
+    #[account(init, payer = admin, seeds = [program.key().as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump, space = 8 + AppRegistration::LEN)]
+    pub app_registration: Account<'info, AppRegistration>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct AppConf<'info> {
+    #[account(constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(mut, seeds = [program.as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump)]
+    pub app_registration: Account<'info, AppRegistration>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct RemoveApp<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(mut, close = admin, seeds = [program.as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump)]
+    pub app_registration: Account<'info, AppRegistration>,
+}
@@ -1,6 +1,17 @@
 use anchor_lang::prelude::*;
 
-use crate::state::config::ConfigInfo;
+use crate::{
+    error::ErrorCode,
+    evnet::{
+        AdminAccepted, AdminProposalCancelled, AdminProposed, BridgePauseChanged,
+        ChainPauseChanged, GuardianChanged, VerifyModeChanged,
+    },
+    state::{
+        config::{Chain, ChainPause, ConfigInfo, VerifyMode},
+        validator::ValidatorSet,
+    },
+    utils::schnorr::check_aggregate_key,
+};
 
 pub fn initialize(ctx: Context<ConfInitialize>, bump: u8) -> Result<()> {
     let bridge_config = &mut ctx.accounts.bridge_config;
@@ -10,8 +21,140 @@ pub fn initialize(ctx: Context<ConfInitialize>, bump: u8) -> Result<()> {
     Ok(())
 }
 
-pub fn change_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
-    ctx.accounts.bridge_config.admin = new_admin;
+/// Propose a new admin, who must accept the handover before it takes effect
+pub fn propose_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
+    require!(new_admin != Pubkey::default(), ErrorCode::InvalidPendingAdmin);
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    bridge_config.pending_admin = new_admin;
+
+    emit!(AdminProposed {
+        admin: bridge_config.admin,
+        pending_admin: new_admin,
+    });
+    Ok(())
+}
+
+/// Accept the admin handover, signed by the pending admin
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    let old_admin = bridge_config.admin;
+    bridge_config.admin = bridge_config.pending_admin;
+    bridge_config.pending_admin = Pubkey::default();
+
+    emit!(AdminAccepted {
+        old_admin,
+        new_admin: bridge_config.admin,
+    });
+    Ok(())
+}
+
+/// Cancel a pending admin handover
+pub fn cancel_admin_proposal(ctx: Context<BridgeConf>) -> Result<()> {
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    require!(
+        bridge_config.pending_admin != Pubkey::default(),
+        ErrorCode::NoPendingAdmin
+    );
+    let pending_admin = bridge_config.pending_admin;
+    bridge_config.pending_admin = Pubkey::default();
+
+    emit!(AdminProposalCancelled {
+        admin: bridge_config.admin,
+        pending_admin,
+    });
+    Ok(())
+}
+
+/// Set the guardian allowed to pause the bridge
+pub fn set_guardian(ctx: Context<BridgeConf>, guardian: Pubkey) -> Result<()> {
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    let old_guardian = bridge_config.guardian;
+    bridge_config.guardian = guardian;
+
+    emit!(GuardianChanged {
+        old_guardian,
+        new_guardian: guardian,
+    });
+    Ok(())
+}
+
+/// Pause send_message and confirm_message, callable by the guardian or the admin
+pub fn pause(ctx: Context<GuardianConf>) -> Result<()> {
+    ctx.accounts.bridge_config.paused = true;
+
+    emit!(BridgePauseChanged {
+        authority: ctx.accounts.authority.key(),
+        paused: true,
+    });
+    Ok(())
+}
+
+/// Unpause the bridge, callable by the admin only
+pub fn unpause(ctx: Context<BridgeConf>) -> Result<()> {
+    ctx.accounts.bridge_config.paused = false;
+
+    emit!(BridgePauseChanged {
+        authority: ctx.accounts.admin.key(),
+        paused: false,
+    });
+    Ok(())
+}
+
+/// Pause outbound sends and/or inbound confirmations of one chain, callable by the guardian or the admin
+pub fn pause_chain(
+    ctx: Context<ChainPauseConf>,
+    chain: Chain,
+    outbound: bool,
+    inbound: bool,
+) -> Result<()> {
+    let chain_pause = &mut ctx.accounts.chain_pause;
+    chain_pause.chain = chain;
+    chain_pause.outbound_paused |= outbound;
+    chain_pause.inbound_paused |= inbound;
+
+    emit!(ChainPauseChanged {
+        authority: ctx.accounts.authority.key(),
+        chain: chain_pause.chain.clone(),
+        outbound_paused: chain_pause.outbound_paused,
+        inbound_paused: chain_pause.inbound_paused,
+    });
+    Ok(())
+}
+
+/// Unpause outbound sends and/or inbound confirmations of one chain, callable by the admin only
+pub fn unpause_chain(
+    ctx: Context<ChainUnpauseConf>,
+    _chain: Chain,
+    outbound: bool,
+    inbound: bool,
+) -> Result<()> {
+    let chain_pause = &mut ctx.accounts.chain_pause;
+    if outbound {
+        chain_pause.outbound_paused = false;
+    }
+    if inbound {
+        chain_pause.inbound_paused = false;
+    }
+
+    emit!(ChainPauseChanged {
+        authority: ctx.accounts.admin.key(),
+        chain: chain_pause.chain.clone(),
+        outbound_paused: chain_pause.outbound_paused,
+        inbound_paused: chain_pause.inbound_paused,
+    });
+    Ok(())
+}
+
+/// Select how confirm_message verifies the validators. VerifyMode::Aggregate uses the
+/// aggregate key of the active validator set, which only a signed rotation can change
+pub fn set_verify_mode(ctx: Context<VerifyModeConf>, mode: VerifyMode) -> Result<()> {
+    let aggregate_pk = ctx.accounts.validator_set.load()?.aggregate_pk;
+    if mode == VerifyMode::Aggregate {
+        check_aggregate_key(&aggregate_pk)?;
+    }
+    ctx.accounts.bridge_config.verify_mode = mode.clone();
+
+    emit!(VerifyModeChanged { mode, aggregate_pk });
     Ok(())
 }
 
@@ -35,3 +178,52 @@ pub struct BridgeConf<'info> {
     #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
     pub bridge_config: Account<'info, ConfigInfo>,
 }
+
+#[derive(Accounts)]
+pub struct VerifyModeConf<'info> {
+    #[account(constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(constraint = pending_admin.key() == bridge_config.pending_admin @ ErrorCode::NoPendingAdmin)]
+    pub pending_admin: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianConf<'info> {
+    #[account(constraint = authority.key() == bridge_config.guardian || authority.key() == bridge_config.admin)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain: Chain)]
+pub struct ChainPauseConf<'info> {
+    #[account(mut, constraint = authority.key() == bridge_config.guardian || authority.key() == bridge_config.admin)]
+    pub authority: Signer<'info>,
+    #[account(init_if_needed, payer = authority, seeds = [&chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump, space = 8 + ChainPause::LEN)]
+    pub chain_pause: Account<'info, ChainPause>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain: Chain)]
+pub struct ChainUnpauseConf<'info> {
+    #[account(constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [&chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: Account<'info, ChainPause>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+}
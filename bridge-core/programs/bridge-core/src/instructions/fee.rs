@@ -0,0 +1,375 @@
+use crate::{
+    error::ErrorCode,
+    evnet::{FeeClaimed, FeeSplitChanged},
+    state::{
+        config::{
+            ConfigInfo, FeeBalance, FeeSplit, ValidatorShare, MAX_FEE_BPS, MESSAGE_FEE_SEED,
+            TREASURY_SEED,
+        },
+        validator::{ValidatorKey, ValidatorRewards, ValidatorSet, ValidatorSetRef},
+    },
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{keccak::hash, secp256k1_recover::secp256k1_recover},
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+
+/// Configure how the collected bridge fees are shared between the treasury,
+/// the validators and the relayers
+#[allow(clippy::too_many_arguments)]
+pub fn set_fee_split(
+    ctx: Context<FeeSplitConf>,
+    treasury: Pubkey,
+    treasury_bps: u16,
+    validator_bps: u16,
+    relayer_bps: u16,
+    validator_share: ValidatorShare,
+    reward_per_message: u64,
+) -> Result<()> {
+    let fee_split = &mut ctx.accounts.fee_split;
+    fee_split.treasury = treasury;
+    fee_split.treasury_bps = treasury_bps;
+    fee_split.validator_bps = validator_bps;
+    fee_split.relayer_bps = relayer_bps;
+    fee_split.validator_share = validator_share.clone();
+    fee_split.reward_per_message = reward_per_message;
+    fee_split.check_bps()?;
+    ctx.accounts.treasury_balance.owner = treasury;
+
+    emit!(FeeSplitChanged {
+        treasury,
+        treasury_bps,
+        validator_bps,
+        relayer_bps,
+        validator_share,
+        reward_per_message,
+    });
+    Ok(())
+}
+
+/// Claim the balance of a relayer or of the treasury
+pub fn claim_fee(ctx: Context<ClaimFee>) -> Result<()> {
+    let amount = std::mem::take(&mut ctx.accounts.fee_balance.claimable);
+    pay_out(
+        &mut ctx.accounts.fee_split,
+        &ctx.accounts.message_fee,
+        &ctx.accounts.owner,
+        amount,
+    )?;
+
+    emit!(FeeClaimed {
+        owner: ctx.accounts.owner.key(),
+        recipient: ctx.accounts.owner.key(),
+        amount,
+    });
+    Ok(())
+}
+
+/// Claim the rewards of the validator at `index` of the set of `epoch` to `recipient`.
+/// An ed25519 validator signs the transaction, an eth validator signs the claim payload.
+pub fn claim_validator_fee(
+    ctx: Context<ClaimValidatorFee>,
+    epoch: u64,
+    index: u16,
+    signature: Option<[u8; 65]>,
+) -> Result<()> {
+    let signer = ValidatorSetRef::new(&ctx.accounts.validator_set.as_ref().try_borrow_data()?)?
+        .validators
+        .get(index as usize)
+        .ok_or(ErrorCode::ValidatorNotFound)?
+        .signer();
+    let authorized = match (&signer, signature) {
+        (ValidatorKey::Ed25519(pubkey), _) => ctx.accounts.claimer.key().to_bytes() == *pubkey,
+        (ValidatorKey::Eth(address), Some(signature)) => {
+            let payload = ValidatorRewards::claim_payload(epoch, index, ctx.accounts.recipient.key);
+            let recovered_pubkey = secp256k1_recover(
+                &hash(&payload).to_bytes(),
+                (signature[64] + 1) % 2,
+                &signature[..64],
+            )
+            .map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+            hash(&recovered_pubkey.0).to_bytes()[12..] == address[..]
+        }
+        (ValidatorKey::Eth(_), None) => false,
+    };
+    require!(authorized, ErrorCode::ValidatorClaimUnauthorized);
+
+    let claimable = ctx
+        .accounts
+        .validator_rewards
+        .claimable
+        .get_mut(index as usize)
+        .ok_or(ErrorCode::ValidatorNotFound)?;
+    let amount = std::mem::take(claimable);
+    pay_out(
+        &mut ctx.accounts.fee_split,
+        &ctx.accounts.message_fee,
+        &ctx.accounts.recipient,
+        amount,
+    )?;
+
+    emit!(FeeClaimed {
+        owner: ctx.accounts.claimer.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+    Ok(())
+}
+
+/// Move the reward of a confirmed message from the vaultFee balance into the
+/// claimable balances, when the admin configured a fee split. The relayer pays for the
+/// balances it credits first, without a split they stay uncreated.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn distribute_reward<'info>(
+    fee_split_info: &AccountInfo<'info>,
+    message_fee: &AccountInfo<'info>,
+    treasury_balance: &AccountInfo<'info>,
+    relayer_balance: &AccountInfo<'info>,
+    validator_rewards: &AccountInfo<'info>,
+    relayer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    validator_set: &ValidatorSetRef,
+    signers: &[ValidatorKey],
+) -> Result<()> {
+    let mut fee_split = match FeeSplit::load(fee_split_info)? {
+        Some(fee_split) => fee_split,
+        None => return Ok(()),
+    };
+    let available = message_fee
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0))
+        .saturating_sub(fee_split.allocated);
+    let reward = fee_split.reward_per_message.min(available);
+    if reward == 0 {
+        return Ok(());
+    }
+
+    let epoch = validator_set.epoch.to_be_bytes();
+    let mut rewards = load_or_create(
+        validator_rewards,
+        relayer,
+        system_program,
+        &[&epoch, ValidatorRewards::SEED_SUFFIX.as_bytes()],
+        8 + ValidatorRewards::space(validator_set.validators.len()),
+        || ValidatorRewards {
+            epoch: validator_set.epoch,
+            claimable: vec![0; validator_set.validators.len()],
+        },
+    )?;
+    let mut treasury = load_or_create(
+        treasury_balance,
+        relayer,
+        system_program,
+        &[TREASURY_SEED.as_bytes(), FeeBalance::SEED_SUFFIX.as_bytes()],
+        8 + FeeBalance::LEN,
+        || FeeBalance {
+            owner: fee_split.treasury,
+            claimable: 0,
+        },
+    )?;
+    let mut relayer_fee = load_or_create(
+        relayer_balance,
+        relayer,
+        system_program,
+        &[relayer.key.as_ref(), FeeBalance::SEED_SUFFIX.as_bytes()],
+        8 + FeeBalance::LEN,
+        || FeeBalance {
+            owner: relayer.key(),
+            claimable: 0,
+        },
+    )?;
+
+    let mut rewarded: Vec<usize> = Vec::with_capacity(validator_set.validators.len());
+    if fee_split.validator_share == ValidatorShare::ProRata {
+        for signer in signers {
+            if let Some(index) = validator_set.index_of(signer) {
+                if !rewarded.contains(&index) {
+                    rewarded.push(index);
+                }
+            }
+        }
+    }
+    if rewarded.is_empty() {
+        rewarded = (0..validator_set.validators.len()).collect();
+    }
+
+    let bps_of = |bps: u16| {
+        reward
+            .checked_mul(bps as u64)
+            .map(|part| part / MAX_FEE_BPS as u64)
+            .ok_or(ErrorCode::FeeOverflow)
+    };
+    let validator_part = bps_of(fee_split.validator_bps)?;
+    let relayer_part = bps_of(fee_split.relayer_bps)?;
+    let per_validator = validator_part / rewarded.len() as u64;
+    for index in rewarded.iter() {
+        let claimable = &mut rewards.claimable[*index];
+        *claimable = claimable.checked_add(per_validator).ok_or(ErrorCode::FeeOverflow)?;
+    }
+    // the rounding dust goes to the treasury
+    let treasury_part = reward
+        .checked_sub(per_validator * rewarded.len() as u64)
+        .and_then(|rest| rest.checked_sub(relayer_part))
+        .ok_or(ErrorCode::FeeOverflow)?;
+    treasury.claimable = treasury
+        .claimable
+        .checked_add(treasury_part)
+        .ok_or(ErrorCode::FeeOverflow)?;
+    relayer_fee.owner = relayer.key();
+    relayer_fee.claimable = relayer_fee
+        .claimable
+        .checked_add(relayer_part)
+        .ok_or(ErrorCode::FeeOverflow)?;
+    fee_split.allocated = fee_split
+        .allocated
+        .checked_add(reward)
+        .ok_or(ErrorCode::FeeOverflow)?;
+
+    store(&rewards, validator_rewards)?;
+    store(&treasury, treasury_balance)?;
+    store(&relayer_fee, relayer_balance)?;
+    fee_split.store(fee_split_info)
+}
+
+/// Deserialize the reward account at the PDA of `seeds`, first creating it at the
+/// expense of `payer` with the value of `init`
+fn load_or_create<'info, T: AccountSerialize + AccountDeserialize>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    init: impl FnOnce() -> T,
+) -> Result<T> {
+    if !info.data_is_empty() {
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+        );
+        return T::try_deserialize(&mut &info.try_borrow_data()?[..]);
+    }
+
+    let (_, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    let bump = [bump];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+    let signer = &[&signer_seeds[..]];
+    let lamports = Rent::get()?.minimum_balance(space);
+    // like Anchor's init, an address someone already sent lamports to is topped up
+    if info.lamports() == 0 {
+        create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                signer,
+            ),
+            lamports,
+            space as u64,
+            &crate::ID,
+        )?;
+    } else {
+        let top_up = lamports.saturating_sub(info.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: info.clone(),
+                },
+                signer,
+            ),
+            space as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Assign {
+                    account_to_assign: info.clone(),
+                },
+                signer,
+            ),
+            &crate::ID,
+        )?;
+    }
+    Ok(init())
+}
+
+fn store<T: AccountSerialize>(value: &T, info: &AccountInfo) -> Result<()> {
+    value.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+fn pay_out(
+    fee_split: &mut FeeSplit,
+    message_fee: &AccountInfo,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    fee_split.allocated = fee_split
+        .allocated
+        .checked_sub(amount)
+        .ok_or(ErrorCode::FeeOverflow)?;
+    message_fee.sub_lamports(amount)?;
+    recipient.add_lamports(amount)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FeeSplitConf<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(init_if_needed, payer = admin, seeds = [FeeSplit::SEEDS.as_bytes()], bump, space = 8 + FeeSplit::LEN)]
+    pub fee_split: Account<'info, FeeSplit>,
+    #[account(init_if_needed, payer = admin, seeds = [TREASURY_SEED.as_bytes(), FeeBalance::SEED_SUFFIX.as_bytes()], bump, space = 8 + FeeBalance::LEN)]
+    pub treasury_balance: Account<'info, FeeBalance>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFee<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, constraint = fee_balance.owner == owner.key())]
+    pub fee_balance: Account<'info, FeeBalance>,
+    #[account(mut, seeds = [FeeSplit::SEEDS.as_bytes()], bump)]
+    pub fee_split: Account<'info, FeeSplit>,
+    /// CHECK:
+    #[account(mut, seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct ClaimValidatorFee<'info> {
+    pub claimer: Signer<'info>,
+    /// CHECK: receives the claimed lamports
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+    #[account(seeds = [&epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    #[account(mut, seeds = [&epoch.to_be_bytes(), ValidatorRewards::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_rewards: Account<'info, ValidatorRewards>,
+    #[account(mut, seeds = [FeeSplit::SEEDS.as_bytes()], bump)]
+    pub fee_split: Account<'info, FeeSplit>,
+    /// CHECK:
+    #[account(mut, seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+}
@@ -1,26 +1,45 @@
-use std::collections::HashSet;
-
-use super::config::BridgeConf;
+use super::{config::BridgeConf, fee::distribute_reward};
 use crate::{
     error::ErrorCode,
-    evnet::{ConfirmMessage, SendMessage},
-    state::config::{
-        Chain, ChainType, ConfigInfo, FromChainNonce, MsgHeader, ToChainNonce, CHAIN_ID,
-        MESSAGE_FEE_SEED,
+    evnet::{ChainFeeChanged, ChannelInitialized, ConfirmMessage, FeeMintChanged, SendMessage},
+    state::{
+        config::{
+            AppRegistration, Chain, ChainFee, ChainPause, ChainType, ConfigInfo, DeliveryMode,
+            FeeBalance, FeeMint, FeeSplit, FromChainNonce, FromChainNonceWindow, LegacyToChainNonce, MessageReceipt,
+            MsgHeader, OutboundRing, OutboundTree, SendQuote, ToChainNonce, VerifyMode,
+            CHAIN_ID, MESSAGE_FEE_SEED, TREASURY_SEED,
+        },
+        validator::{ValidatorKey, ValidatorRewards, ValidatorSet, ValidatorSetRef},
+    },
+    utils::{
+        digest::{message_hash, typed_message},
+        schnorr::verify_schnorr,
+        sigverify::{ed25519_signers, secp256k1_signers},
     },
 };
 use anchor_lang::{
     prelude::*,
     solana_program::{
         keccak::hash, program::invoke, secp256k1_recover::secp256k1_recover, system_instruction,
+        sysvar::instructions as sysvar_instructions,
     },
 };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
 
-/// Initialize the to chain nonce
+/// Initialize the to chain nonce of the channel from a source app to a destination app
 pub fn init_to_chain_nonce_account(
-    _ctx: Context<InitSendToChainNonce>,
-    _to_chain: Chain,
+    ctx: Context<InitSendToChainNonce>,
+    to_chain: Chain,
+    from_addr: Pubkey,
+    to_addr: [u8; 32],
 ) -> Result<()> {
+    let to_chain_nonce_account = &mut ctx.accounts.to_chain_nonce_account;
+    to_chain_nonce_account.chain = to_chain;
+    to_chain_nonce_account.from_addr = from_addr.to_bytes();
+    to_chain_nonce_account.to_addr = to_addr;
     Ok(())
 }
 
@@ -30,10 +49,33 @@ pub fn set_bridge_fee(ctx: Context<BridgeConf>, bridge_fee: u64) -> Result<()> {
     Ok(())
 }
 
+/// Set the bridge fee schedule of messages to `chain`, replacing the flat bridge_fee
+pub fn set_chain_fee(
+    ctx: Context<ChainFeeConf>,
+    chain: Chain,
+    base_fee: u64,
+    byte_fee: u64,
+) -> Result<()> {
+    let chain_fee = &mut ctx.accounts.chain_fee;
+    chain_fee.chain = chain;
+    chain_fee.base_fee = base_fee;
+    chain_fee.byte_fee = byte_fee;
+
+    emit!(ChainFeeChanged {
+        chain: chain_fee.chain.clone(),
+        base_fee,
+        byte_fee,
+    });
+    Ok(())
+}
+
 /// withdraw the bridge fee
 pub fn withdraw_fee(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     let message_fee_account = &mut ctx.accounts.message_fee;
-    if message_fee_account.lamports() >= amount {
+    // lamports owed to the claimable balances stay in the vault
+    let allocated = FeeSplit::load(&ctx.accounts.fee_split)?.map_or(0, |fee_split| fee_split.allocated);
+    let required = amount.checked_add(allocated).ok_or(ErrorCode::FeeOverflow)?;
+    if message_fee_account.lamports() >= required {
         ctx.accounts.message_fee.sub_lamports(amount)?;
         ctx.accounts.super_admin.add_lamports(amount)?;
     } else {
@@ -42,7 +84,108 @@ pub fn withdraw_fee(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     Ok(())
 }
 
-/// Send a message to another chain
+/// Accept `token_mint` for bridge fees at `tokens_per_sol` token units per SOL of fee,
+/// creating its fee vault on first use
+pub fn set_fee_mint(ctx: Context<FeeMintConf>, tokens_per_sol: u64, enabled: bool) -> Result<()> {
+    require!(
+        !enabled || tokens_per_sol > 0,
+        crate::error::ErrorCode::InvalidFeeMintRate
+    );
+    let fee_mint = &mut ctx.accounts.fee_mint;
+    fee_mint.mint = ctx.accounts.token_mint.key();
+    fee_mint.tokens_per_sol = tokens_per_sol;
+    fee_mint.enabled = enabled;
+
+    emit!(FeeMintChanged {
+        mint: fee_mint.mint,
+        tokens_per_sol,
+        enabled,
+    });
+    Ok(())
+}
+
+/// withdraw the bridge fee paid in a fee mint
+pub fn withdraw_token_fee(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.fee_vault.amount >= amount,
+        crate::error::ErrorCode::InsufficientFee
+    );
+    let seeds = &[MESSAGE_FEE_SEED.as_bytes(), &[ctx.bumps.message_fee]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.receiver_token.to_account_info(),
+                authority: ctx.accounts.message_fee.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        amount,
+    )?;
+    Ok(())
+}
+
+/// Initialize the channel from a source app on a chain to the calling app, which picks its delivery mode
+pub fn init_channel(
+    ctx: Context<InitChannel>,
+    from_chain: Chain,
+    from_addr: [u8; 32],
+    delivery_mode: DeliveryMode,
+) -> Result<()> {
+    let mut from_chain_nonce_account = ctx.accounts.from_chain_nonce_account.load_init()?;
+    from_chain_nonce_account.chain_type = from_chain.chain_type;
+    from_chain_nonce_account.chain_id = from_chain.chain_id;
+    from_chain_nonce_account.from_addr = from_addr;
+    from_chain_nonce_account.to_addr = ctx.accounts.caller_program.key().to_bytes();
+    from_chain_nonce_account.delivery_mode = delivery_mode as u8;
+
+    emit!(ChannelInitialized {
+        from_chain,
+        from_addr,
+        caller_program: ctx.accounts.caller_program.key(),
+        delivery_mode,
+    });
+    Ok(())
+}
+
+/// Merge the legacy FromChainNonce of `receiver` into a channel the app opened with
+/// init_channel and close it, admin only. Run once per receiver of the channel.
+pub fn migrate_from_chain_nonce(
+    ctx: Context<MigrateFromChainNonce>,
+    _from_chain: Chain,
+    _from_addr: [u8; 32],
+) -> Result<()> {
+    let legacy_info = &ctx.accounts.legacy_nonce_account;
+    let legacy = FromChainNonce::try_deserialize(&mut &legacy_info.try_borrow_data()?[..])?;
+    ctx.accounts.from_chain_nonce_account.load_mut()?.merge_legacy(&legacy)?;
+
+    let lamports = legacy_info.lamports();
+    legacy_info.sub_lamports(lamports)?;
+    ctx.accounts.admin.add_lamports(lamports)?;
+    legacy_info.assign(&System::id());
+    legacy_info.resize(0)?;
+    Ok(())
+}
+
+/// Continue the nonces of an outbound channel after the legacy counter of its chain,
+/// admin only. Run for every channel that sent before the upgrade, see the README.
+pub fn migrate_to_chain_nonce(
+    ctx: Context<MigrateToChainNonce>,
+    to_chain: Chain,
+    from_addr: Pubkey,
+    to_addr: [u8; 32],
+) -> Result<()> {
+    let legacy = LegacyToChainNonce::load(&ctx.accounts.legacy_nonce_account)?;
+    let to_chain_nonce_account = &mut ctx.accounts.to_chain_nonce_account;
+    to_chain_nonce_account.chain = to_chain;
+    to_chain_nonce_account.from_addr = from_addr.to_bytes();
+    to_chain_nonce_account.to_addr = to_addr;
+    to_chain_nonce_account.max_nonce = to_chain_nonce_account.max_nonce.max(legacy.max_nonce);
+    Ok(())
+}
+
+/// Send a message to another chain, from an app registered with register_app
 pub fn send_message(
     ctx: Context<SendToOtherChain>,
     to_chain: Chain,
@@ -51,8 +194,10 @@ pub fn send_message(
     mtype: u8,
     upload_fee: u64,
 ) -> Result<()> {
+    ctx.accounts.check_send(&to_chain, mtype)?;
+
     // transfer bridge fee to message fee account
-    let bridge_fee = ctx.accounts.bridge_config.bridge_fee;
+    let bridge_fee = ctx.accounts.message_fee(mbody.len())?;
     invoke(
         &system_instruction::transfer(
             ctx.accounts.sender.key,
@@ -66,98 +211,222 @@ pub fn send_message(
         ],
     )?;
 
-    // update message nonce
-    let to_chain_nonce_account = &mut ctx.accounts.to_chain_nonce_account;
-    if to_chain_nonce_account.max_nonce == 0 {
-        to_chain_nonce_account.chain = to_chain.clone();
-    }
-    to_chain_nonce_account.max_nonce += 1;
-
-    // Generate the message header
-    let mheader = MsgHeader {
-        mtype: mtype,
-        nonce: to_chain_nonce_account.max_nonce,
-        from_chain: get_current_chain(),
-        from_addr: ctx.accounts.caller_program.key().to_bytes(),
-        to_chain: to_chain,
-        to_addr: to_addr,
-        upload_gas_fee: upload_fee as u128,
-    };
+    ctx.accounts.publish(to_chain, to_addr, mbody, mtype, upload_fee, None, bridge_fee)
+}
 
-    // Emit the message
-    emit!(SendMessage {
-        header: mheader,
-        body: mbody,
-        fee: upload_fee
-    });
+/// Send a message to another chain, paying the bridge fee in an accepted fee mint
+/// at its fixed price into the fee vault
+pub fn send_message_with_token(
+    ctx: Context<SendToOtherChainWithToken>,
+    to_chain: Chain,
+    to_addr: [u8; 32],
+    mbody: Vec<u8>,
+    mtype: u8,
+    upload_fee: u64,
+) -> Result<()> {
+    ctx.accounts.send.check_send(&to_chain, mtype)?;
 
-    Ok(())
+    // transfer bridge fee to the fee vault
+    let bridge_fee = ctx
+        .accounts
+        .fee_mint
+        .token_fee(ctx.accounts.send.message_fee(mbody.len())?)?;
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.sender_token.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.send.sender.to_account_info(),
+            },
+        ),
+        bridge_fee,
+    )?;
+
+    let fee_mint = Some(ctx.accounts.token_mint.key());
+    ctx.accounts
+        .send
+        .publish(to_chain, to_addr, mbody, mtype, upload_fee, fee_mint, bridge_fee)
 }
 
-/// Confirm a message of another chain
+/// Quote the bridge fee send_message charges the caller program for a body of `body_len`
+/// bytes to `to_chain`, returned through the return data
+pub fn quote_send(ctx: Context<QuoteSend>, _to_chain: Chain, body_len: u32) -> Result<SendQuote> {
+    let bridge_fee = message_fee(
+        &ctx.accounts.bridge_config,
+        &ctx.accounts.chain_fee,
+        &ctx.accounts.app_registration,
+        body_len as usize,
+    )?;
+    Ok(SendQuote { bridge_fee })
+}
+
+/// Bridge fee of a message: the app fee override, else the chain fee schedule,
+/// else the flat ConfigInfo.bridge_fee
+fn message_fee(
+    bridge_config: &ConfigInfo,
+    chain_fee: &AccountInfo,
+    app_registration: &AppRegistration,
+    body_len: usize,
+) -> Result<u64> {
+    let chain_fee = match ChainFee::load(chain_fee)? {
+        Some(chain_fee) => chain_fee.fee(body_len)?,
+        None => bridge_config.bridge_fee,
+    };
+    Ok(app_registration.message_fee(chain_fee))
+}
+
+/// Confirm a message of another chain, whose typed data (see utils::digest) the validators signed.
+///
+/// In VerifyMode::Multisig it is signed by eth validators through `signatures` and by
+/// ed25519 validators through Ed25519Program instructions in the same transaction.
+/// In VerifyMode::Aggregate `accum_pk` is the aggregate key of the epoch and `signatures`
+/// holds a single Schnorr signature `e || s || 0`.
 pub fn confirm_message(
     ctx: Context<ConfirmFromOtherChain>,
     msg_header: MsgHeader,
     msg_body: Vec<u8>,
-    _accum_pk: Vec<u8>,
+    accum_pk: Vec<u8>,
     signatures: Vec<[u8; 65]>,
 ) -> Result<()> {
-    // check bridge token message
-    if msg_body.len() >= 112 {
-        let to_who_bytes = &msg_body[80..112];
+    ctx.accounts.check_message(&msg_body)?;
+
+    let validator_data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let signers = verify_message_signatures(
+        &ctx.accounts.bridge_config,
+        &ValidatorSetRef::new(&validator_data)?,
+        &ctx.accounts.instructions,
+        &msg_header,
+        &msg_body,
+        &accum_pk,
+        &signatures,
+    )?;
+    drop(validator_data);
+
+    ctx.accounts.consume_message(msg_header, msg_body, &signers)
+}
+
+/// Confirm a message of another chain whose validator signatures were checked by
+/// Secp256k1Program and Ed25519Program instructions in the same transaction.
+/// Only in VerifyMode::Multisig, an aggregate deployment accepts no individual signers.
+pub fn confirm_message_precompiled(
+    ctx: Context<ConfirmFromOtherChain>,
+    msg_header: MsgHeader,
+    msg_body: Vec<u8>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.bridge_config.verify_mode == VerifyMode::Multisig,
+        crate::error::ErrorCode::PrecompiledInAggregateMode
+    );
+    ctx.accounts.check_message(&msg_body)?;
+
+    let validator_data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let validator_set = ValidatorSetRef::new(&validator_data)?;
+    let message = typed_message(&crate::ID, CHAIN_ID, &msg_header, &msg_body, validator_set.epoch);
+
+    let mut signers: Vec<ValidatorKey> = secp256k1_signers(&ctx.accounts.instructions, &message)?
+        .into_iter()
+        .map(ValidatorKey::Eth)
+        .collect();
+    signers.extend(ed25519_precompiled_signers(&ctx.accounts.instructions, &message)?);
+    validator_set.verify_quorum(&signers)?;
+    msg!("All {} signatures verified successfully!", signers.len());
+    drop(validator_data);
+
+    ctx.accounts.consume_message(msg_header, msg_body, &signers)
+}
+
+/// Check inbound messages are not paused globally or for the source chain
+pub(crate) fn check_inbound(bridge_config: &ConfigInfo, chain_pause: &AccountInfo) -> Result<()> {
+    require!(
+        !bridge_config.paused,
+        crate::error::ErrorCode::BridgePaused
+    );
+    if let Some(chain_pause) = ChainPause::load(chain_pause)? {
         require!(
-            to_who_bytes == ctx.accounts.receiver.key().to_bytes(),
-            crate::error::ErrorCode::ReceiverMismatch
+            !chain_pause.inbound_paused,
+            crate::error::ErrorCode::ChainInboundPaused
         );
     }
+    Ok(())
+}
 
-    let validators = ctx.accounts.bridge_config.signers.clone();
-    let mut message = msg_header.to_be_bytes();
-    message.extend(msg_body.clone());
+/// Verify the validators of the active epoch signed the typed data of a message,
+/// in the verify mode of the deployment, returning the individual signers
+pub(crate) fn verify_message_signatures(
+    bridge_config: &ConfigInfo,
+    validator_set: &ValidatorSetRef,
+    instructions: &AccountInfo,
+    msg_header: &MsgHeader,
+    msg_body: &[u8],
+    accum_pk: &[u8],
+    signatures: &[[u8; 65]],
+) -> Result<Vec<ValidatorKey>> {
+    let message = typed_message(&crate::ID, CHAIN_ID, msg_header, msg_body, validator_set.epoch);
+    verify_signatures(bridge_config, validator_set, instructions, &message, accum_pk, signatures)
+}
 
-    let threshold = ctx.accounts.bridge_config.threshold;
-    verify_multisig(message, signatures, validators, threshold)?;
+/// Verify the validators of the active epoch signed `message`, in the verify mode of
+/// the deployment, returning the individual signers
+pub(crate) fn verify_signatures(
+    bridge_config: &ConfigInfo,
+    validator_set: &ValidatorSetRef,
+    instructions: &AccountInfo,
+    message: &[u8],
+    accum_pk: &[u8],
+    signatures: &[[u8; 65]],
+) -> Result<Vec<ValidatorKey>> {
+    match bridge_config.verify_mode {
+        VerifyMode::Multisig => verify_multisig(message, signatures, instructions, validator_set),
+        VerifyMode::Aggregate => {
+            verify_aggregate(message, accum_pk, signatures, &validator_set.aggregate_pk)?;
+            Ok(Vec::new())
+        }
+    }
+}
 
+/// Record a verified message in its channel and its receipt, returning the message hash
+pub(crate) fn record_delivery(
+    from_chain_nonce_account: &AccountLoader<FromChainNonceWindow>,
+    message_receipt: &mut MessageReceipt,
+    executor: Pubkey,
+    msg_header: &MsgHeader,
+    msg_body: &[u8],
+) -> Result<[u8; 32]> {
     // update message nonce
-    let from_chain_nonce_account = &mut ctx.accounts.from_chain_nonce_account;
-    if from_chain_nonce_account.last_nonce == 0 {
-        from_chain_nonce_account.chain = msg_header.from_chain.clone();
-    }
+    let mut from_chain_nonce_account = from_chain_nonce_account.load_mut()?;
 
     msg!(
         "msg_header.nonce {}, from_chain_nonce_account.last_nonce {}",
         msg_header.nonce,
         from_chain_nonce_account.last_nonce
     );
-    // check if the message is already confirmed
-    require!(
-        from_chain_nonce_account.last_nonce != msg_header.nonce,
-        crate::error::ErrorCode::NonceConsumed
-    );
-    from_chain_nonce_account.check_and_store_nonce(msg_header.nonce)?;
-    from_chain_nonce_account.last_nonce = msg_header.nonce;
-
-    emit!(ConfirmMessage {
-        executor: ctx.accounts.user.key(),
-        from_chain: msg_header.from_chain,
-        nonce: msg_header.nonce,
-        mbody: msg_body.clone(),
-    });
-    Ok(())
+    from_chain_nonce_account.record(msg_header.nonce)?;
+
+    // record the execution
+    let clock = Clock::get()?;
+    let message_hash = message_hash(msg_header, msg_body);
+    message_receipt.message_hash = message_hash;
+    message_receipt.from_chain = msg_header.from_chain.clone();
+    message_receipt.nonce = msg_header.nonce;
+    message_receipt.executor = executor;
+    message_receipt.executed_at = clock.unix_timestamp;
+    message_receipt.slot = clock.slot;
+    Ok(message_hash)
 }
 
 /// Verify a multisig message of another chain
-fn verify_multisig(
-    message: Vec<u8>,
-    signatures: Vec<[u8; 65]>,
-    validators: Vec<[u8; 20]>,
-    threshold: u8,
-) -> Result<()> {
-    let mut signers = HashSet::new();
-    // check every validator
+pub(crate) fn verify_multisig(
+    message: &[u8],
+    signatures: &[[u8; 65]],
+    instructions: &AccountInfo,
+    validator_set: &ValidatorSetRef,
+) -> Result<Vec<ValidatorKey>> {
+    let mut signers = ed25519_precompiled_signers(instructions, message)?;
+    // recover every validator
     for signature in signatures.iter() {
         let recovered_pubkey = secp256k1_recover(
-            &hash(&message).to_bytes(),
+            &hash(message).to_bytes(),
             (signature[64] + 1) % 2,
             &signature[..64],
         )
@@ -166,21 +435,44 @@ fn verify_multisig(
         let hash = hash(&recovered_pubkey.0).to_bytes();
         let mut validator = [0u8; 20];
         validator.copy_from_slice(&hash[12..]);
-
-        require!(
-            validators.contains(&validator),
-            crate::error::ErrorCode::SignaturePublicKeyMismatch
-        );
-        signers.insert(validator);
+        signers.push(ValidatorKey::Eth(validator));
     }
+    validator_set.verify_quorum(&signers)?;
+    msg!("All {} signatures verified successfully!", signers.len());
+    Ok(signers)
+}
+
+/// Verify the aggregate Schnorr signature of a message of another chain
+pub(crate) fn verify_aggregate(
+    message: &[u8],
+    accum_pk: &[u8],
+    signatures: &[[u8; 65]],
+    aggregate_pk: &[u8; 33],
+) -> Result<()> {
     require!(
-        signers.len() >= threshold as usize,
-        crate::error::ErrorCode::SignaturesLess
+        accum_pk == aggregate_pk,
+        crate::error::ErrorCode::AggregateKeyMismatch
     );
-    msg!("All {} signatures verified successfully!", signatures.len());
+    let signature = match signatures {
+        [signature] if signature[64] == 0 => signature,
+        _ => return Err(ErrorCode::SignatureVerificationFailed.into()),
+    };
+    verify_schnorr(
+        aggregate_pk,
+        &hash(message).to_bytes(),
+        signature[..64].try_into().unwrap(),
+    )?;
+    msg!("Aggregate signature verified successfully!");
     Ok(())
 }
 
+fn ed25519_precompiled_signers(instructions: &AccountInfo, message: &[u8]) -> Result<Vec<ValidatorKey>> {
+    Ok(ed25519_signers(instructions, message)?
+        .into_iter()
+        .map(ValidatorKey::Ed25519)
+        .collect())
+}
+
 fn get_current_chain() -> Chain {
     Chain {
         chain_type: ChainType::Solana as u8,
@@ -189,17 +481,29 @@ fn get_current_chain() -> Chain {
 }
 
 #[derive(Accounts)]
-#[instruction(to_chain: Chain)]
+#[instruction(to_chain: Chain, from_addr: Pubkey, to_addr: [u8; 32])]
 pub struct InitSendToChainNonce<'info> {
     #[account(mut, constraint = sender.key() == bridge_config.admin)]
     pub sender: Signer<'info>,
-    #[account(init, payer = sender, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes()], bump, space = 8 + ToChainNonce::LEN)]
+    #[account(init, payer = sender, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes(), from_addr.as_ref(), &to_addr], bump, space = 8 + ToChainNonce::LEN)]
     pub to_chain_nonce_account: Account<'info, ToChainNonce>,
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
     pub bridge_config: Account<'info, ConfigInfo>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(chain: Chain)]
+pub struct ChainFeeConf<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(init_if_needed, payer = admin, seeds = [&chain.combain_chain(), ChainFee::SEED_SUFFIX.as_bytes()], bump, space = 8 + ChainFee::LEN)]
+    pub chain_fee: Account<'info, ChainFee>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     #[account(mut, constraint = super_admin.key() == bridge_config.admin)]
@@ -207,16 +511,75 @@ pub struct Withdraw<'info> {
     /// CHECK:
     #[account(mut, seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
     pub message_fee: AccountInfo<'info>,
+    /// CHECK: may be uninitialized when fees are not distributed
+    #[account(seeds = [FeeSplit::SEEDS.as_bytes()], bump)]
+    pub fee_split: AccountInfo<'info>,
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
     pub bridge_config: Account<'info, ConfigInfo>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(to_chain: Chain)]
+#[instruction(from_chain: Chain, from_addr: [u8; 32])]
+pub struct InitChannel<'info> {
+    #[account(init, payer = payer, seeds = [&from_chain.combain_chain(), FromChainNonceWindow::SEED_SUFFIX.as_bytes(), &from_addr, caller_program.key().as_ref()], bump, space = 8 + FromChainNonceWindow::LEN)]
+    pub from_chain_nonce_account: AccountLoader<'info, FromChainNonceWindow>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"bridge"],
+        bump,
+        seeds::program = caller_program.key()
+    )]
+    pub caller_auth_pda: Signer<'info>,
+    /// CHECK:
+    pub caller_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(from_chain: Chain, from_addr: [u8; 32])]
+pub struct MigrateFromChainNonce<'info> {
+    /// CHECK: legacy FromChainNonce, checked by its discriminator
+    #[account(mut, owner = crate::ID, seeds = [&from_chain.combain_chain(), FromChainNonce::SEED_SUFFIX.as_bytes(), receiver.key().as_ref()], bump)]
+    pub legacy_nonce_account: AccountInfo<'info>,
+    /// CHECK: the receiver the legacy account was kept for
+    pub receiver: AccountInfo<'info>,
+    /// The channel the app opened with init_channel
+    #[account(mut, seeds = [&from_chain.combain_chain(), FromChainNonceWindow::SEED_SUFFIX.as_bytes(), &from_addr, caller_program.key().as_ref()], bump)]
+    pub from_chain_nonce_account: AccountLoader<'info, FromChainNonceWindow>,
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK:
+    pub caller_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(to_chain: Chain, from_addr: Pubkey, to_addr: [u8; 32])]
+pub struct MigrateToChainNonce<'info> {
+    /// CHECK: legacy ToChainNonce, checked by its discriminator
+    #[account(owner = crate::ID, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes()], bump)]
+    pub legacy_nonce_account: AccountInfo<'info>,
+    #[account(init_if_needed, payer = admin, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes(), from_addr.as_ref(), &to_addr], bump, space = 8 + ToChainNonce::LEN)]
+    pub to_chain_nonce_account: Account<'info, ToChainNonce>,
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(to_chain: Chain, to_addr: [u8; 32])]
 pub struct SendToOtherChain<'info> {
-    #[account(mut, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes()], bump)]
+    #[account(init_if_needed, payer = sender, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes(), caller_program.key().as_ref(), &to_addr], bump, space = 8 + ToChainNonce::LEN)]
     pub to_chain_nonce_account: Account<'info, ToChainNonce>,
+    #[account(init_if_needed, payer = sender, seeds = [&to_chain.combain_chain(), OutboundRing::SEED_SUFFIX.as_bytes(), caller_program.key().as_ref(), &to_addr], bump, space = 8 + OutboundRing::LEN)]
+    pub outbound_ring: AccountLoader<'info, OutboundRing>,
+    #[account(init_if_needed, payer = sender, seeds = [&to_chain.combain_chain(), OutboundTree::SEED_SUFFIX.as_bytes()], bump, space = 8 + OutboundTree::LEN)]
+    pub outbound_tree: AccountLoader<'info, OutboundTree>,
     #[account(mut)]
     pub sender: Signer<'info>,
     /// CHECK:
@@ -224,6 +587,14 @@ pub struct SendToOtherChain<'info> {
     pub message_fee: AccountInfo<'info>,
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
     pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&to_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    /// CHECK: may be uninitialized when the chain has no fee schedule
+    #[account(seeds = [&to_chain.combain_chain(), ChainFee::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_fee: AccountInfo<'info>,
+    #[account(seeds = [caller_program.key().as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump)]
+    pub app_registration: Account<'info, AppRegistration>,
     #[account(
         seeds = [b"bridge"],
         bump,
@@ -235,13 +606,196 @@ pub struct SendToOtherChain<'info> {
     pub system_program: Program<'info, System>,
 }
 
+impl<'info> SendToOtherChain<'info> {
+    /// Check outbound messages are not paused and the app may send this message
+    fn check_send(&self, to_chain: &Chain, mtype: u8) -> Result<()> {
+        require!(
+            !self.bridge_config.paused,
+            crate::error::ErrorCode::BridgePaused
+        );
+        if let Some(chain_pause) = ChainPause::load(&self.chain_pause)? {
+            require!(
+                !chain_pause.outbound_paused,
+                crate::error::ErrorCode::ChainOutboundPaused
+            );
+        }
+        self.app_registration.check_send(to_chain, mtype)
+    }
+
+    /// Bridge fee of the message in lamports
+    fn message_fee(&self, body_len: usize) -> Result<u64> {
+        message_fee(
+            &self.bridge_config,
+            &self.chain_fee,
+            &self.app_registration,
+            body_len,
+        )
+    }
+
+    /// Assign the next nonce of the channel, commit the message and emit it
+    #[allow(clippy::too_many_arguments)]
+    fn publish(
+        &mut self,
+        to_chain: Chain,
+        to_addr: [u8; 32],
+        mbody: Vec<u8>,
+        mtype: u8,
+        upload_fee: u64,
+        fee_mint: Option<Pubkey>,
+        bridge_fee: u64,
+    ) -> Result<()> {
+        // update message nonce
+        let to_chain_nonce_account = &mut self.to_chain_nonce_account;
+        if to_chain_nonce_account.max_nonce == 0 {
+            to_chain_nonce_account.chain = to_chain.clone();
+            to_chain_nonce_account.from_addr = self.caller_program.key().to_bytes();
+            to_chain_nonce_account.to_addr = to_addr;
+        }
+        to_chain_nonce_account.max_nonce += 1;
+
+        // Generate the message header
+        let mheader = MsgHeader {
+            mtype: mtype,
+            nonce: to_chain_nonce_account.max_nonce,
+            from_chain: get_current_chain(),
+            from_addr: self.caller_program.key().to_bytes(),
+            to_chain: to_chain,
+            to_addr: to_addr,
+            upload_gas_fee: upload_fee as u128,
+        };
+
+        // commit the message on chain
+        let message_hash = message_hash(&mheader, &mbody);
+        let mut outbound_ring = match self.outbound_ring.load_mut() {
+            Ok(outbound_ring) => outbound_ring,
+            Err(_) => self.outbound_ring.load_init()?,
+        };
+        if outbound_ring.chain_id == 0 {
+            outbound_ring.chain_type = mheader.to_chain.chain_type;
+            outbound_ring.chain_id = mheader.to_chain.chain_id;
+            outbound_ring.from_addr = mheader.from_addr;
+            outbound_ring.to_addr = mheader.to_addr;
+        }
+        outbound_ring.record(mheader.nonce, message_hash, Clock::get()?.slot);
+        let mut outbound_tree = match self.outbound_tree.load_mut() {
+            Ok(outbound_tree) => outbound_tree,
+            Err(_) => self.outbound_tree.load_init()?,
+        };
+        if outbound_tree.chain_id == 0 {
+            outbound_tree.chain_type = mheader.to_chain.chain_type;
+            outbound_tree.chain_id = mheader.to_chain.chain_id;
+        }
+        let leaf_index = outbound_tree.append(message_hash)?;
+
+        // Emit the message
+        emit!(SendMessage {
+            message_hash,
+            leaf_index,
+            header: mheader,
+            body: mbody,
+            fee: upload_fee,
+            fee_mint,
+            bridge_fee,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(to_chain: Chain, to_addr: [u8; 32])]
+pub struct SendToOtherChainWithToken<'info> {
+    pub send: SendToOtherChain<'info>,
+    #[account(seeds = [token_mint.key().as_ref(), FeeMint::SEED_SUFFIX.as_bytes()], bump)]
+    pub fee_mint: Account<'info, FeeMint>,
+    pub token_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = token_mint, token::authority = send.sender)]
+    pub sender_token: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = send.message_fee)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FeeMintConf<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    pub token_mint: Account<'info, Mint>,
+    #[account(init_if_needed, payer = admin, seeds = [token_mint.key().as_ref(), FeeMint::SEED_SUFFIX.as_bytes()], bump, space = 8 + FeeMint::LEN)]
+    pub fee_mint: Account<'info, FeeMint>,
+    /// CHECK:
+    #[account(seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+    #[account(init_if_needed, payer = admin, associated_token::mint = token_mint, associated_token::authority = message_fee)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
-#[instruction(msg_header: MsgHeader)]
+pub struct WithdrawToken<'info> {
+    #[account(constraint = super_admin.key() == bridge_config.admin)]
+    pub super_admin: Signer<'info>,
+    /// CHECK:
+    #[account(seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    pub token_mint: Account<'info, Mint>,
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = message_fee)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = token_mint)]
+    pub receiver_token: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(to_chain: Chain)]
+pub struct QuoteSend<'info> {
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain has no fee schedule
+    #[account(seeds = [&to_chain.combain_chain(), ChainFee::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_fee: AccountInfo<'info>,
+    #[account(seeds = [caller_program.key().as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump)]
+    pub app_registration: Account<'info, AppRegistration>,
+    /// CHECK:
+    pub caller_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader, msg_body: Vec<u8>)]
 pub struct ConfirmFromOtherChain<'info> {
-    #[account(init_if_needed, payer = user, seeds = [&msg_header.from_chain.combain_chain(), FromChainNonce::SEED_SUFFIX.as_bytes(), receiver.key().as_ref()], bump, space = 8 + FromChainNonce::LEN)]
-    pub from_chain_nonce_account: Account<'info, FromChainNonce>,
+    /// The channel the receiving app opened with init_channel
+    #[account(mut, seeds = [&msg_header.from_chain.combain_chain(), FromChainNonceWindow::SEED_SUFFIX.as_bytes(), &msg_header.from_addr, &msg_header.to_addr], bump)]
+    pub from_chain_nonce_account: AccountLoader<'info, FromChainNonceWindow>,
+    #[account(init, payer = user, seeds = [&message_hash(&msg_header, &msg_body), MessageReceipt::SEED_SUFFIX.as_bytes()], bump, space = 8 + MessageReceipt::LEN)]
+    pub message_receipt: Account<'info, MessageReceipt>,
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
     pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    /// CHECK: may be uninitialized when fees are not distributed
+    #[account(mut, seeds = [FeeSplit::SEEDS.as_bytes()], bump)]
+    pub fee_split: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+    /// CHECK: created by set_fee_split
+    #[account(mut, seeds = [TREASURY_SEED.as_bytes(), FeeBalance::SEED_SUFFIX.as_bytes()], bump)]
+    pub treasury_balance: AccountInfo<'info>,
+    /// CHECK: created by the first reward of the relayer, when a fee split exists
+    #[account(mut, seeds = [user.key().as_ref(), FeeBalance::SEED_SUFFIX.as_bytes()], bump)]
+    pub relayer_balance: AccountInfo<'info>,
+    /// CHECK: created by the first reward of the epoch, when a fee split exists
+    #[account(mut, seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorRewards::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_rewards: AccountInfo<'info>,
     #[account(mut)]
     pub user: Signer<'info>,
     /// CHECK:
@@ -255,5 +809,61 @@ pub struct ConfirmFromOtherChain<'info> {
     /// CHECK:
     #[account(mut, constraint = msg_header.to_addr == caller_program.key().to_bytes())]
     pub caller_program: AccountInfo<'info>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
 }
+
+impl<'info> ConfirmFromOtherChain<'info> {
+    /// Checks shared by every confirm path, run before the signatures are verified
+    pub(crate) fn check_message(&self, msg_body: &[u8]) -> Result<()> {
+        check_inbound(&self.bridge_config, &self.chain_pause)?;
+
+        // check bridge token message
+        if msg_body.len() >= 112 {
+            let to_who_bytes = &msg_body[80..112];
+            require!(
+                to_who_bytes == self.receiver.key().to_bytes(),
+                crate::error::ErrorCode::ReceiverMismatch
+            );
+        }
+        Ok(())
+    }
+
+    /// Consume the nonce of a verified message, reward its relayer and signers and emit it
+    pub(crate) fn consume_message(
+        &mut self,
+        msg_header: MsgHeader,
+        msg_body: Vec<u8>,
+        signers: &[ValidatorKey],
+    ) -> Result<()> {
+        let message_hash = record_delivery(
+            &self.from_chain_nonce_account,
+            &mut self.message_receipt,
+            self.user.key(),
+            &msg_header,
+            &msg_body,
+        )?;
+        distribute_reward(
+            &self.fee_split,
+            &self.message_fee,
+            &self.treasury_balance,
+            &self.relayer_balance,
+            &self.validator_rewards,
+            &self.user,
+            &self.system_program,
+            &ValidatorSetRef::new(&self.validator_set.as_ref().try_borrow_data()?)?,
+            signers,
+        )?;
+
+        emit!(ConfirmMessage {
+            message_hash,
+            executor: self.user.key(),
+            from_chain: msg_header.from_chain,
+            nonce: msg_header.nonce,
+            mbody: msg_body,
+        });
+        Ok(())
+    }
+}
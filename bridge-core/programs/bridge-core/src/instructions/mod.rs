@@ -1,3 +1,8 @@
+pub mod app;
 pub mod config;
+pub mod fee;
 pub mod message;
+pub mod optimistic;
+pub mod pending;
+pub mod root;
 pub mod validator;
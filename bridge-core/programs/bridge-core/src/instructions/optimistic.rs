@@ -0,0 +1,289 @@
+use crate::{
+    error::ErrorCode,
+    evnet::{AttestationChallenged, AttestationPosted, ChainVerificationChanged},
+    state::{
+        config::{
+            Attestation, Chain, ChainPause, ChainVerification, ChainVerifyMode, ConfigInfo,
+            MsgHeader, CHAIN_ID, MIN_CHALLENGE_PERIOD,
+        },
+        validator::{ValidatorSet, ValidatorSetRef},
+    },
+    utils::{
+        digest::{message_hash, typed_fraud},
+        merkle::verify_proof,
+    },
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions as sysvar_instructions,
+    system_program::{transfer, Transfer},
+};
+
+use super::message::*;
+
+/// Select how the messages of `chain` are verified, for ChainVerifyMode::Optimistic
+/// the challenge period in seconds, at least MIN_CHALLENGE_PERIOD, and the nonzero
+/// minimum bond of an attestation in lamports, both unused by the other modes
+pub fn set_chain_verification(
+    ctx: Context<ChainVerificationConf>,
+    chain: Chain,
+    mode: ChainVerifyMode,
+    challenge_period: i64,
+    min_bond: u64,
+) -> Result<()> {
+    if mode == ChainVerifyMode::Optimistic {
+        require!(
+            challenge_period >= MIN_CHALLENGE_PERIOD,
+            ErrorCode::ChallengePeriodTooShort
+        );
+        require!(min_bond > 0, ErrorCode::MinBondZero);
+    }
+
+    let chain_verification = &mut ctx.accounts.chain_verification;
+    chain_verification.chain = chain.clone();
+    chain_verification.mode = mode.clone();
+    chain_verification.challenge_period = challenge_period;
+    chain_verification.min_bond = min_bond;
+
+    emit!(ChainVerificationChanged {
+        chain,
+        mode,
+        challenge_period,
+        min_bond,
+    });
+    Ok(())
+}
+
+/// Attest the Merkle root of the first `count` messages `from_chain` sent to this chain,
+/// bonding `bond` lamports until the challenge period is over
+pub fn post_attestation(
+    ctx: Context<PostAttestation>,
+    from_chain: Chain,
+    root: [u8; 32],
+    count: u64,
+    bond: u64,
+) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+    let chain_verification = check_optimistic(&ctx.accounts.chain_verification)?;
+    require!(bond >= chain_verification.min_bond, ErrorCode::BondTooLow);
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.attester.to_account_info(),
+                to: ctx.accounts.attestation.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let attestation = &mut ctx.accounts.attestation;
+    attestation.from_chain = from_chain.clone();
+    attestation.root = root;
+    attestation.count = count;
+    attestation.attester = ctx.accounts.attester.key();
+    attestation.bond = bond;
+    attestation.posted_at = now;
+    attestation.challenge_ends_at = now
+        .checked_add(chain_verification.challenge_period)
+        .ok_or(ErrorCode::ChallengeEndOverflow)?;
+
+    emit!(AttestationPosted {
+        from_chain,
+        root,
+        count,
+        attester: attestation.attester,
+        bond,
+        challenge_ends_at: attestation.challenge_ends_at,
+    });
+    Ok(())
+}
+
+/// Cancel an attestation in its challenge period with a fraud claim the validators of
+/// the active epoch signed for this challenger (see utils::digest), its bond goes to
+/// the challenger
+pub fn challenge_attestation(
+    ctx: Context<ChallengeAttestation>,
+    accum_pk: Vec<u8>,
+    signatures: Vec<[u8; 65]>,
+) -> Result<()> {
+    let attestation = &ctx.accounts.attestation;
+    require!(
+        Clock::get()?.unix_timestamp < attestation.challenge_ends_at,
+        ErrorCode::ChallengePeriodOver
+    );
+
+    let validator_data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let validator_set = ValidatorSetRef::new(&validator_data)?;
+    let message = typed_fraud(
+        &crate::ID,
+        CHAIN_ID,
+        &attestation.from_chain,
+        &attestation.root,
+        attestation.count,
+        validator_set.epoch,
+        &ctx.accounts.challenger.key(),
+    );
+    verify_signatures(
+        &ctx.accounts.bridge_config,
+        &validator_set,
+        &ctx.accounts.instructions,
+        &message,
+        &accum_pk,
+        &signatures,
+    )?;
+
+    let attestation = &mut ctx.accounts.attestation;
+    let slashed = std::mem::take(&mut attestation.bond);
+    attestation.cancelled = true;
+    attestation.sub_lamports(slashed)?;
+    ctx.accounts.challenger.add_lamports(slashed)?;
+
+    emit!(AttestationChallenged {
+        from_chain: attestation.from_chain.clone(),
+        root: attestation.root,
+        attester: attestation.attester,
+        challenger: ctx.accounts.challenger.key(),
+        slashed,
+    });
+    Ok(())
+}
+
+/// Return the bond of an unchallenged attestation once its challenge period is over,
+/// the attestation stays to confirm its messages
+pub fn release_bond(ctx: Context<ReleaseBond>) -> Result<()> {
+    let attestation = &mut ctx.accounts.attestation;
+    require!(
+        Clock::get()?.unix_timestamp >= attestation.challenge_ends_at,
+        ErrorCode::ChallengePeriodActive
+    );
+    let bond = std::mem::take(&mut attestation.bond);
+    attestation.sub_lamports(bond)?;
+    ctx.accounts.attester.add_lamports(bond)?;
+    Ok(())
+}
+
+/// Confirm a message of an optimistic chain by its inclusion proof under an attestation
+/// whose challenge period is over, `leaf_index` being its position in the outbound tree
+/// of the source chain
+pub fn confirm_message_optimistic(
+    ctx: Context<ConfirmOptimistic>,
+    msg_header: MsgHeader,
+    msg_body: Vec<u8>,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.confirm.check_message(&msg_body)?;
+    check_optimistic(&ctx.accounts.chain_verification)?;
+
+    let attestation = &ctx.accounts.attestation;
+    require!(
+        Clock::get()?.unix_timestamp >= attestation.challenge_ends_at,
+        ErrorCode::ChallengePeriodActive
+    );
+    require!(leaf_index < attestation.count, ErrorCode::LeafIndexOutOfRange);
+    require!(
+        verify_proof(
+            &message_hash(&msg_header, &msg_body),
+            leaf_index,
+            &proof,
+            &attestation.root,
+        ),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    // no validator signed the message, every validator of the set shares the reward
+    ctx.accounts.confirm.consume_message(msg_header, msg_body, &[])
+}
+
+/// The verification of a chain in ChainVerifyMode::Optimistic
+fn check_optimistic(chain_verification: &AccountInfo) -> Result<ChainVerification> {
+    match ChainVerification::load(chain_verification)? {
+        Some(chain_verification) if chain_verification.mode == ChainVerifyMode::Optimistic => {
+            Ok(chain_verification)
+        }
+        _ => Err(ErrorCode::ChainNotOptimistic.into()),
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(chain: Chain)]
+pub struct ChainVerificationConf<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(init_if_needed, payer = admin, seeds = [&chain.combain_chain(), ChainVerification::SEED_SUFFIX.as_bytes()], bump, space = 8 + ChainVerification::LEN)]
+    pub chain_verification: Account<'info, ChainVerification>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(from_chain: Chain, root: [u8; 32])]
+pub struct PostAttestation<'info> {
+    #[account(init, payer = attester, seeds = [&from_chain.combain_chain(), root.as_ref(), Attestation::SEED_SUFFIX.as_bytes()], bump, space = 8 + Attestation::LEN)]
+    pub attestation: Account<'info, Attestation>,
+    /// CHECK: may be uninitialized when the chain was never configured
+    #[account(seeds = [&from_chain.combain_chain(), ChainVerification::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_verification: AccountInfo<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    #[account(mut)]
+    pub attester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [&attestation.from_chain.combain_chain(), attestation.root.as_ref(), Attestation::SEED_SUFFIX.as_bytes()],
+        bump,
+        constraint = !attestation.cancelled @ ErrorCode::AttestationCancelled
+    )]
+    pub attestation: Account<'info, Attestation>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseBond<'info> {
+    #[account(
+        mut,
+        seeds = [&attestation.from_chain.combain_chain(), attestation.root.as_ref(), Attestation::SEED_SUFFIX.as_bytes()],
+        bump,
+        has_one = attester,
+        constraint = !attestation.cancelled @ ErrorCode::AttestationCancelled
+    )]
+    pub attestation: Account<'info, Attestation>,
+    #[account(mut)]
+    pub attester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader)]
+pub struct ConfirmOptimistic<'info> {
+    pub confirm: ConfirmFromOtherChain<'info>,
+    #[account(
+        seeds = [&attestation.from_chain.combain_chain(), attestation.root.as_ref(), Attestation::SEED_SUFFIX.as_bytes()],
+        bump,
+        constraint = attestation.from_chain == msg_header.from_chain @ ErrorCode::RootChainMismatch,
+        constraint = !attestation.cancelled @ ErrorCode::AttestationCancelled
+    )]
+    pub attestation: Account<'info, Attestation>,
+    /// CHECK: may be uninitialized when the chain was never configured
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), ChainVerification::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_verification: AccountInfo<'info>,
+}
@@ -0,0 +1,264 @@
+use crate::{
+    error::ErrorCode,
+    evnet::{ConfirmMessage, MessageExecutionFailed, MessageVerified},
+    state::{
+        config::{
+            ChainPause, ConfigInfo, DeliveryMode, FeeBalance, FeeSplit, FromChainNonceWindow,
+            MessageReceipt, MessageStatus, MsgHeader, PendingMessage, DELIVERY_AUTHORITY_SEED,
+            MESSAGE_FEE_SEED, TREASURY_SEED,
+        },
+        validator::{ValidatorRewards, ValidatorSet, ValidatorSetRef},
+    },
+    utils::{digest::message_hash, receiver::receive_message_data},
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        sysvar::instructions as sysvar_instructions,
+    },
+};
+
+use super::{
+    fee::distribute_reward,
+    message::{check_inbound, record_delivery, verify_message_signatures},
+};
+
+/// Verify a message of another chain and store it for its destination app to execute.
+/// Permissionless: the signatures bind the message, so any relayer can submit it.
+/// Pending messages execute in any order, so Ordered channels are confirmed directly.
+pub fn verify_message(
+    ctx: Context<VerifyFromOtherChain>,
+    msg_header: MsgHeader,
+    msg_body: Vec<u8>,
+    accum_pk: Vec<u8>,
+    signatures: Vec<[u8; 65]>,
+) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+    require!(
+        ctx.accounts.from_chain_nonce_account.load()?.delivery_mode() == DeliveryMode::Unordered,
+        ErrorCode::OrderedChannelPending
+    );
+
+    let validator_data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let validator_set = ValidatorSetRef::new(&validator_data)?;
+    let signers = verify_message_signatures(
+        &ctx.accounts.bridge_config,
+        &validator_set,
+        &ctx.accounts.instructions,
+        &msg_header,
+        &msg_body,
+        &accum_pk,
+        &signatures,
+    )?;
+
+    let message_hash = record_delivery(
+        &ctx.accounts.from_chain_nonce_account,
+        &mut ctx.accounts.message_receipt,
+        ctx.accounts.user.key(),
+        &msg_header,
+        &msg_body,
+    )?;
+    distribute_reward(
+        &ctx.accounts.fee_split,
+        &ctx.accounts.message_fee,
+        &ctx.accounts.treasury_balance,
+        &ctx.accounts.relayer_balance,
+        &ctx.accounts.validator_rewards,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        &validator_set,
+        &signers,
+    )?;
+
+    let pending_message = &mut ctx.accounts.pending_message;
+    pending_message.message_hash = message_hash;
+    pending_message.header = msg_header;
+    pending_message.body = msg_body;
+    pending_message.status = MessageStatus::Verified;
+    pending_message.verifier = ctx.accounts.user.key();
+    pending_message.verified_at = Clock::get()?.unix_timestamp;
+
+    emit!(MessageVerified {
+        message_hash,
+        verifier: ctx.accounts.user.key(),
+        from_chain: pending_message.header.from_chain.clone(),
+        nonce: pending_message.header.nonce,
+    });
+    Ok(())
+}
+
+/// Mark a pending message executed, called by its destination app once it has acted on it.
+/// The pending message is closed to its verifier, the receipt still blocks a replay.
+pub fn execute_message(ctx: Context<ExecuteMessage>) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+
+    let pending_message = &ctx.accounts.pending_message;
+    emit!(ConfirmMessage {
+        message_hash: pending_message.message_hash,
+        executor: ctx.accounts.executor.key(),
+        from_chain: pending_message.header.from_chain.clone(),
+        nonce: pending_message.header.nonce,
+        mbody: pending_message.body.clone(),
+    });
+    pending_message.close(ctx.accounts.verifier.to_account_info())
+}
+
+/// Record a failed execution attempt, the message stays executable
+pub fn fail_message(ctx: Context<ExecuteMessage>) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+
+    let pending_message = &mut ctx.accounts.pending_message;
+    pending_message.status = MessageStatus::Failed;
+    pending_message.executor = ctx.accounts.executor.key();
+    pending_message.attempts += 1;
+
+    emit!(MessageExecutionFailed {
+        message_hash: pending_message.message_hash,
+        executor: ctx.accounts.executor.key(),
+        attempts: pending_message.attempts,
+    });
+    Ok(())
+}
+
+/// Deliver a pending message to its destination program by calling its
+/// `receive_message(msg_header, msg_body)` with its delivery authority as signer,
+/// followed by `remaining_accounts`. A failing receiver reverts the delivery and the
+/// message stays pending, a delivered one is closed to its verifier.
+pub fn deliver_message<'info>(ctx: Context<'_, '_, '_, 'info, DeliverMessage<'info>>) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+
+    let pending_message = &ctx.accounts.pending_message;
+
+    let mut accounts = vec![AccountMeta::new_readonly(ctx.accounts.delivery_authority.key(), true)];
+    let mut account_infos = vec![ctx.accounts.delivery_authority.to_account_info()];
+    for account in ctx.remaining_accounts {
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(ctx.accounts.receiver_program.to_account_info());
+
+    invoke_signed(
+        &Instruction {
+            program_id: ctx.accounts.receiver_program.key(),
+            accounts,
+            data: receive_message_data(&pending_message.header, &pending_message.body)?,
+        },
+        &account_infos,
+        &[&[
+            DELIVERY_AUTHORITY_SEED.as_bytes(),
+            ctx.accounts.receiver_program.key().as_ref(),
+            &[ctx.bumps.delivery_authority],
+        ]],
+    )?;
+
+    emit!(ConfirmMessage {
+        message_hash: pending_message.message_hash,
+        executor: ctx.accounts.executor.key(),
+        from_chain: pending_message.header.from_chain.clone(),
+        nonce: pending_message.header.nonce,
+        mbody: pending_message.body.clone(),
+    });
+    pending_message.close(ctx.accounts.verifier.to_account_info())
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader, msg_body: Vec<u8>)]
+pub struct VerifyFromOtherChain<'info> {
+    /// The channel the receiving app opened with init_channel
+    #[account(mut, seeds = [&msg_header.from_chain.combain_chain(), FromChainNonceWindow::SEED_SUFFIX.as_bytes(), &msg_header.from_addr, &msg_header.to_addr], bump)]
+    pub from_chain_nonce_account: AccountLoader<'info, FromChainNonceWindow>,
+    #[account(init, payer = user, seeds = [&message_hash(&msg_header, &msg_body), MessageReceipt::SEED_SUFFIX.as_bytes()], bump, space = 8 + MessageReceipt::LEN)]
+    pub message_receipt: Account<'info, MessageReceipt>,
+    #[account(init, payer = user, seeds = [&message_hash(&msg_header, &msg_body), PendingMessage::SEED_SUFFIX.as_bytes()], bump, space = 8 + PendingMessage::space(msg_body.len()))]
+    pub pending_message: Account<'info, PendingMessage>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    /// CHECK: may be uninitialized when fees are not distributed
+    #[account(mut, seeds = [FeeSplit::SEEDS.as_bytes()], bump)]
+    pub fee_split: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [MESSAGE_FEE_SEED.as_bytes()], bump)]
+    pub message_fee: AccountInfo<'info>,
+    /// CHECK: created by set_fee_split
+    #[account(mut, seeds = [TREASURY_SEED.as_bytes(), FeeBalance::SEED_SUFFIX.as_bytes()], bump)]
+    pub treasury_balance: AccountInfo<'info>,
+    /// CHECK: created by the first reward of the relayer, when a fee split exists
+    #[account(mut, seeds = [user.key().as_ref(), FeeBalance::SEED_SUFFIX.as_bytes()], bump)]
+    pub relayer_balance: AccountInfo<'info>,
+    /// CHECK: created by the first reward of the epoch, when a fee split exists
+    #[account(mut, seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorRewards::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_rewards: AccountInfo<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMessage<'info> {
+    #[account(
+        mut,
+        seeds = [&pending_message.message_hash, PendingMessage::SEED_SUFFIX.as_bytes()],
+        bump,
+        constraint = pending_message.header.to_addr == caller_program.key().to_bytes() @ ErrorCode::CallerProgramMismatch,
+        constraint = pending_message.status != MessageStatus::Executed @ ErrorCode::MessageAlreadyExecuted
+    )]
+    pub pending_message: Account<'info, PendingMessage>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&pending_message.header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    /// CHECK: paid the rent of the pending message, refunded when it is executed
+    #[account(mut, address = pending_message.verifier)]
+    pub verifier: AccountInfo<'info>,
+    pub executor: Signer<'info>,
+    #[account(
+        seeds = [b"bridge"],
+        bump,
+        seeds::program = caller_program.key()
+    )]
+    pub caller_auth_pda: Signer<'info>,
+    /// CHECK:
+    pub caller_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeliverMessage<'info> {
+    #[account(
+        mut,
+        seeds = [&pending_message.message_hash, PendingMessage::SEED_SUFFIX.as_bytes()],
+        bump,
+        constraint = pending_message.header.to_addr == receiver_program.key().to_bytes() @ ErrorCode::CallerProgramMismatch,
+        constraint = pending_message.status != MessageStatus::Executed @ ErrorCode::MessageAlreadyExecuted
+    )]
+    pub pending_message: Account<'info, PendingMessage>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&pending_message.header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    /// CHECK: signs the receive_message call, one per receiver program
+    #[account(seeds = [DELIVERY_AUTHORITY_SEED.as_bytes(), receiver_program.key().as_ref()], bump)]
+    pub delivery_authority: AccountInfo<'info>,
+    /// CHECK: paid the rent of the pending message, refunded when it is delivered
+    #[account(mut, address = pending_message.verifier)]
+    pub verifier: AccountInfo<'info>,
+    pub executor: Signer<'info>,
+    /// CHECK:
+    #[account(executable)]
+    pub receiver_program: AccountInfo<'info>,
+}
@@ -0,0 +1,124 @@
+use crate::{
+    error::ErrorCode,
+    evnet::RootPosted,
+    state::{
+        config::{Chain, ChainPause, ConfigInfo, MsgHeader, RootRecord, CHAIN_ID},
+        validator::{ValidatorSet, ValidatorSetRef},
+    },
+    utils::{
+        digest::{message_hash, typed_root},
+        merkle::verify_proof,
+    },
+};
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions as sysvar_instructions};
+
+use super::message::*;
+
+/// Post the Merkle root of the first `count` messages `from_chain` sent to this chain,
+/// signed by the validators of the active epoch like a message (see utils::digest).
+/// Permissionless: the signatures bind the root, so any relayer can submit it.
+pub fn post_root(
+    ctx: Context<PostRoot>,
+    from_chain: Chain,
+    root: [u8; 32],
+    count: u64,
+    accum_pk: Vec<u8>,
+    signatures: Vec<[u8; 65]>,
+) -> Result<()> {
+    check_inbound(&ctx.accounts.bridge_config, &ctx.accounts.chain_pause)?;
+
+    let validator_data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let validator_set = ValidatorSetRef::new(&validator_data)?;
+    let epoch = validator_set.epoch;
+    let message = typed_root(&crate::ID, CHAIN_ID, &from_chain, &root, count, epoch);
+    verify_signatures(
+        &ctx.accounts.bridge_config,
+        &validator_set,
+        &ctx.accounts.instructions,
+        &message,
+        &accum_pk,
+        &signatures,
+    )?;
+
+    let root_record = &mut ctx.accounts.root_record;
+    root_record.from_chain = from_chain.clone();
+    root_record.root = root;
+    root_record.count = count;
+    root_record.epoch = epoch;
+    root_record.poster = ctx.accounts.user.key();
+    root_record.posted_at = Clock::get()?.unix_timestamp;
+
+    emit!(RootPosted {
+        from_chain,
+        root,
+        count,
+        epoch,
+        poster: ctx.accounts.user.key(),
+    });
+    Ok(())
+}
+
+/// Confirm a message of another chain by its inclusion proof under a root posted by the
+/// active epoch, `leaf_index` being its position in the outbound tree of the source chain
+pub fn confirm_message_with_proof(
+    ctx: Context<ConfirmWithProof>,
+    msg_header: MsgHeader,
+    msg_body: Vec<u8>,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.confirm.check_message(&msg_body)?;
+
+    let root_record = &ctx.accounts.root_record;
+    // the roots of a rotated out set are as untrusted as the set
+    require!(
+        root_record.epoch == ctx.accounts.confirm.bridge_config.epoch,
+        ErrorCode::RootEpochRetired
+    );
+    require!(leaf_index < root_record.count, ErrorCode::LeafIndexOutOfRange);
+    require!(
+        verify_proof(
+            &message_hash(&msg_header, &msg_body),
+            leaf_index,
+            &proof,
+            &root_record.root,
+        ),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    // the root was signed as a whole, every validator of the set shares the reward
+    ctx.accounts.confirm.consume_message(msg_header, msg_body, &[])
+}
+
+#[derive(Accounts)]
+#[instruction(from_chain: Chain, root: [u8; 32])]
+pub struct PostRoot<'info> {
+    /// One per epoch, so a root can be posted again by the set that replaced its signers
+    #[account(init, payer = user, seeds = [&from_chain.combain_chain(), root.as_ref(), &bridge_config.epoch.to_be_bytes(), RootRecord::SEED_SUFFIX.as_bytes()], bump, space = 8 + RootRecord::LEN)]
+    pub root_record: Account<'info, RootRecord>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    /// CHECK: may be uninitialized when the chain was never paused
+    #[account(seeds = [&from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump)]
+    pub chain_pause: AccountInfo<'info>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader)]
+pub struct ConfirmWithProof<'info> {
+    pub confirm: ConfirmFromOtherChain<'info>,
+    #[account(
+        seeds = [&root_record.from_chain.combain_chain(), root_record.root.as_ref(), &root_record.epoch.to_be_bytes(), RootRecord::SEED_SUFFIX.as_bytes()],
+        bump,
+        constraint = root_record.from_chain == msg_header.from_chain @ ErrorCode::RootChainMismatch
+    )]
+    pub root_record: Account<'info, RootRecord>,
+}
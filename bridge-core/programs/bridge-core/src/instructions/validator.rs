@@ -1,47 +1,292 @@
-use std::cmp::min;
+use crate::{
+    error::ErrorCode,
+    evnet::ValidatorSetRotated,
+    state::{
+        config::{ConfigInfo, VerifyMode},
+        validator::{
+            Quorum, Validator, ValidatorKey, ValidatorSet, ValidatorSetRef, MAX_VALIDATORS,
+        },
+    },
+    utils::schnorr::check_aggregate_key,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions as sysvar_instructions,
+    system_program::{transfer, Transfer},
+};
 
-use crate::error::ErrorCode;
-use anchor_lang::prelude::*;
+use super::message::verify_multisig;
 
-use super::config::BridgeConf;
-
-pub fn add_signers(ctx: Context<BridgeConf>, new_signers: Vec<[u8; 20]>) -> Result<()> {
+/// Move the legacy ConfigInfo.signers into the staged validator set of epoch 1.
+/// Inbound messages fail until init_validator_epoch activates it, see the README
+pub fn migrate_validator_set(ctx: Context<MigrateValidatorSet>) -> Result<()> {
     let bridge_config = &mut ctx.accounts.bridge_config;
+    let validators: Vec<Validator> = std::mem::take(&mut bridge_config.signers)
+        .into_iter()
+        .map(|signer| Validator {
+            signer: ValidatorKey::Eth(signer),
+            weight: 1,
+        })
+        .collect();
+
+    let mut validator_set = ctx.accounts.validator_set.load_init()?;
+    validator_set.epoch = 1;
+    validator_set.set_quorum(&Quorum::Weight(bridge_config.threshold as u64));
+    drop(validator_set);
+    ValidatorSet::store_validators(
+        &mut ctx.accounts.validator_set.as_ref().try_borrow_mut_data()?,
+        &validators,
+    )
+}
+
+/// Stage the validator set of the next epoch, starting from a copy of the active set
+pub fn init_validator_set(ctx: Context<InitValidatorSet>) -> Result<()> {
+    let active_data = ctx.accounts.active_set.as_ref().try_borrow_data()?;
+    let active_set = ValidatorSetRef::new(&active_data)?;
+    let mut validator_set = ctx.accounts.validator_set.load_init()?;
+    validator_set.epoch = active_set.epoch + 1;
+    validator_set.set_quorum(&active_set.quorum());
+    validator_set.aggregate_pk = active_set.aggregate_pk;
+    drop(validator_set);
+    ValidatorSet::store_validators(
+        &mut ctx.accounts.validator_set.as_ref().try_borrow_mut_data()?,
+        &active_set.to_validators(),
+    )
+}
+
+pub fn add_signers(ctx: Context<AddSigners>, new_signers: Vec<ValidatorKey>) -> Result<()> {
+    let info = ctx.accounts.validator_set.to_account_info();
+    let mut validators = ValidatorSetRef::new(&info.try_borrow_data()?)?.to_validators();
     for signer in new_signers {
-        if !bridge_config.signers.contains(&signer) {
-            bridge_config.signers.push(signer);
+        if !validators.iter().any(|v| v.signer == signer) {
+            validators.push(Validator { signer, weight: 1 });
         }
     }
     require!(
-        bridge_config.signers.len() <= 12,
-        crate::error::ErrorCode::ValidatorOver12
+        validators.len() <= MAX_VALIDATORS,
+        crate::error::ErrorCode::ValidatorSetFull
     );
-    Ok(())
+    resize_and_store(
+        &info,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        &validators,
+    )
 }
 
-pub fn remove_signers(ctx: Context<BridgeConf>, signers_to_remove: Vec<[u8; 20]>) -> Result<()> {
-    let bridge_config = &mut ctx.accounts.bridge_config;
-    bridge_config
-        .signers
-        .retain(|signer| !signers_to_remove.contains(signer));
+pub fn remove_signers(ctx: Context<RemoveSigners>, signers_to_remove: Vec<ValidatorKey>) -> Result<()> {
+    let info = ctx.accounts.validator_set.to_account_info();
+    let mut validators = ValidatorSetRef::new(&info.try_borrow_data()?)?.to_validators();
+    validators.retain(|validator| !signers_to_remove.contains(&validator.signer));
 
-    if bridge_config.signers.len() == 0 {
+    if validators.is_empty() {
         return Err(ErrorCode::InvalidThreshold.into());
     }
 
-    bridge_config.threshold = min(bridge_config.threshold, bridge_config.signers.len() as u8);
+    let total_weight: u128 = validators.iter().map(|v| v.weight as u128).sum();
+    {
+        let mut validator_set = ctx.accounts.validator_set.load_mut()?;
+        if let Quorum::Weight(weight) = validator_set.quorum() {
+            if weight as u128 > total_weight {
+                validator_set.set_quorum(&Quorum::Weight(total_weight as u64));
+            }
+        }
+    }
 
-    Ok(())
+    resize_and_store(
+        &info,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        &validators,
+    )
 }
 
-/// Sets the threshold for the multisig account.
-pub fn set_threshold(ctx: Context<BridgeConf>, new_threshold: u8) -> Result<()> {
-    let bridge_config = &mut ctx.accounts.bridge_config;
+/// Store `validators` in the staged set, resizing its account to fit them: the admin
+/// pays the rent of a grown set and gets back the rent a shrunk set frees
+fn resize_and_store<'info>(
+    info: &AccountInfo<'info>,
+    admin: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    validators: &[Validator],
+) -> Result<()> {
+    let space = 8 + ValidatorSet::space(validators.len());
+    let rent = Rent::get()?.minimum_balance(space);
+    if space > info.data_len() {
+        let top_up = rent.saturating_sub(info.lamports());
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: admin.clone(),
+                    to: info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    info.resize(space)?;
+    let refund = info.lamports().saturating_sub(rent);
+    if refund > 0 {
+        info.sub_lamports(refund)?;
+        admin.add_lamports(refund)?;
+    }
+    ValidatorSet::store_validators(&mut info.try_borrow_mut_data()?, validators)
+}
 
-    if new_threshold == 0 || new_threshold as usize > bridge_config.signers.len() {
-        return Err(ErrorCode::InvalidThreshold.into());
+/// Sets the quorum, as a total weight or a basis-point fraction of the total weight
+pub fn set_quorum(ctx: Context<ValidatorConf>, quorum: Quorum) -> Result<()> {
+    ctx.accounts.validator_set.load_mut()?.set_quorum(&quorum);
+    ValidatorSetRef::new(&ctx.accounts.validator_set.as_ref().try_borrow_data()?)?.check_quorum()
+}
+
+/// Sets the aggregate key the staged set threshold-signs with in VerifyMode::Aggregate,
+/// activated with the set by a signed rotation
+pub fn set_aggregate_key(ctx: Context<ValidatorConf>, aggregate_pk: [u8; 33]) -> Result<()> {
+    check_aggregate_key(&aggregate_pk)?;
+    ctx.accounts.validator_set.load_mut()?.aggregate_pk = aggregate_pk;
+    Ok(())
+}
+
+/// Sets the voting power of existing signers
+pub fn set_signer_weights(ctx: Context<ValidatorConf>, weights: Vec<Validator>) -> Result<()> {
+    let info = ctx.accounts.validator_set.as_ref();
+    let mut data = info.try_borrow_mut_data()?;
+    let mut validators = ValidatorSetRef::new(&data)?.to_validators();
+    for weight in weights {
+        require!(weight.weight > 0, ErrorCode::InvalidWeight);
+        let validator = validators
+            .iter_mut()
+            .find(|v| v.signer == weight.signer)
+            .ok_or(ErrorCode::ValidatorNotFound)?;
+        validator.weight = weight.weight;
     }
+    ValidatorSet::store_validators(&mut data, &validators)
+}
+
+/// Activate the staged genesis validator set as epoch 1,
+/// after which the set can only change through a signed rotation
+pub fn init_validator_epoch(ctx: Context<InitValidatorEpoch>) -> Result<()> {
+    let data = ctx.accounts.validator_set.as_ref().try_borrow_data()?;
+    let validator_set = ValidatorSetRef::new(&data)?;
+    validator_set.check_quorum()?;
+    check_set_verify_mode(&ctx.accounts.bridge_config, &validator_set)?;
+    ctx.accounts.bridge_config.epoch = validator_set.epoch;
 
-    bridge_config.threshold = new_threshold;
+    emit!(ValidatorSetRotated {
+        epoch: validator_set.epoch,
+        validators: validator_set.to_validators(),
+        quorum: validator_set.quorum(),
+        aggregate_pk: validator_set.aggregate_pk,
+    });
     Ok(())
 }
+
+/// Rotate to the staged validator set, signed by a quorum of the current set.
+/// Ed25519 validators sign the payload through Ed25519Program instructions
+pub fn rotate_validators(ctx: Context<RotateValidators>, signatures: Vec<[u8; 65]>) -> Result<()> {
+    let current_data = ctx.accounts.current_set.as_ref().try_borrow_data()?;
+    let current_set = ValidatorSetRef::new(&current_data)?;
+    let next_data = ctx.accounts.next_set.as_ref().try_borrow_data()?;
+    let next_set = ValidatorSetRef::new(&next_data)?;
+    next_set.check_quorum()?;
+    check_set_verify_mode(&ctx.accounts.bridge_config, &next_set)?;
+
+    let payload = ValidatorSet::rotation_payload(current_set.epoch, &next_set);
+    verify_multisig(&payload, &signatures, &ctx.accounts.instructions, &current_set)?;
+    ctx.accounts.bridge_config.epoch = next_set.epoch;
+
+    emit!(ValidatorSetRotated {
+        epoch: next_set.epoch,
+        validators: next_set.to_validators(),
+        quorum: next_set.quorum(),
+        aggregate_pk: next_set.aggregate_pk,
+    });
+    Ok(())
+}
+
+/// A set activated in VerifyMode::Aggregate needs an aggregate key
+fn check_set_verify_mode(bridge_config: &ConfigInfo, validator_set: &ValidatorSetRef) -> Result<()> {
+    if bridge_config.verify_mode == VerifyMode::Aggregate {
+        check_aggregate_key(&validator_set.aggregate_pk)?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateValidatorSet<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump, constraint = bridge_config.epoch == 0 @ ErrorCode::ValidatorSetSealed)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(init, payer = admin, seeds = [&1u64.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump, space = 8 + ValidatorSet::space(bridge_config.signers.len()))]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitValidatorSet<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub active_set: AccountLoader<'info, ValidatorSet>,
+    #[account(init, payer = admin, seeds = [&(bridge_config.epoch + 1).to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump, space = 8 + ValidatorSet::space(active_set.load()?.count as usize))]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddSigners<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(mut, seeds = [&(bridge_config.epoch + 1).to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveSigners<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(mut, seeds = [&(bridge_config.epoch + 1).to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ValidatorConf<'info> {
+    #[account(constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(mut, seeds = [&(bridge_config.epoch + 1).to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+}
+
+#[derive(Accounts)]
+pub struct InitValidatorEpoch<'info> {
+    #[account(constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump, constraint = bridge_config.epoch == 0 @ ErrorCode::ValidatorSetSealed)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&1u64.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub validator_set: AccountLoader<'info, ValidatorSet>,
+}
+
+#[derive(Accounts)]
+pub struct RotateValidators<'info> {
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&bridge_config.epoch.to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub current_set: AccountLoader<'info, ValidatorSet>,
+    #[account(seeds = [&(bridge_config.epoch + 1).to_be_bytes(), ValidatorSet::SEED_SUFFIX.as_bytes()], bump)]
+    pub next_set: AccountLoader<'info, ValidatorSet>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
@@ -1,16 +1,21 @@
 use anchor_lang::prelude::*;
 use instructions::{
+    app::{self, *},
     config::{self, *},
+    fee::{self, *},
     message::{self, *},
-    validator::{self},
+    optimistic::{self, *},
+    pending::{self, *},
+    root::{self, *},
+    validator::{self, *},
 };
-use state::config::*;
+use state::{config::*, validator::*};
 
-mod error;
+pub mod error;
 mod evnet;
 pub mod instructions;
 pub mod state;
-mod utils;
+pub mod utils;
 
 declare_id!("E9y3Whtskj2Jt4JBQZTenn5pvVthupucpSXfozhQFhQW");
 
@@ -23,39 +28,163 @@ pub mod bridge_core {
         Ok(())
     }
 
-    pub fn change_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
-        config::change_admin(ctx, new_admin)?;
+    pub fn propose_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
+        config::propose_admin(ctx, new_admin)?;
         Ok(())
     }
 
-    pub fn set_threshold(ctx: Context<BridgeConf>, new_threshold: u8) -> Result<()> {
-        validator::set_threshold(ctx, new_threshold)?;
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        config::accept_admin(ctx)?;
         Ok(())
     }
 
-    pub fn add_signers(ctx: Context<BridgeConf>, new_signers: Vec<[u8; 20]>) -> Result<()> {
+    pub fn cancel_admin_proposal(ctx: Context<BridgeConf>) -> Result<()> {
+        config::cancel_admin_proposal(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<BridgeConf>, guardian: Pubkey) -> Result<()> {
+        config::set_guardian(ctx, guardian)?;
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<GuardianConf>) -> Result<()> {
+        config::pause(ctx)?;
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<BridgeConf>) -> Result<()> {
+        config::unpause(ctx)?;
+        Ok(())
+    }
+
+    pub fn pause_chain(
+        ctx: Context<ChainPauseConf>,
+        chain: Chain,
+        outbound: bool,
+        inbound: bool,
+    ) -> Result<()> {
+        config::pause_chain(ctx, chain, outbound, inbound)?;
+        Ok(())
+    }
+
+    pub fn unpause_chain(
+        ctx: Context<ChainUnpauseConf>,
+        chain: Chain,
+        outbound: bool,
+        inbound: bool,
+    ) -> Result<()> {
+        config::unpause_chain(ctx, chain, outbound, inbound)?;
+        Ok(())
+    }
+
+    pub fn set_verify_mode(ctx: Context<VerifyModeConf>, mode: VerifyMode) -> Result<()> {
+        config::set_verify_mode(ctx, mode)?;
+        Ok(())
+    }
+
+    pub fn migrate_validator_set(ctx: Context<MigrateValidatorSet>) -> Result<()> {
+        validator::migrate_validator_set(ctx)?;
+        Ok(())
+    }
+
+    pub fn init_validator_set(ctx: Context<InitValidatorSet>) -> Result<()> {
+        validator::init_validator_set(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_quorum(ctx: Context<ValidatorConf>, quorum: Quorum) -> Result<()> {
+        validator::set_quorum(ctx, quorum)?;
+        Ok(())
+    }
+
+    pub fn set_aggregate_key(ctx: Context<ValidatorConf>, aggregate_pk: [u8; 33]) -> Result<()> {
+        validator::set_aggregate_key(ctx, aggregate_pk)?;
+        Ok(())
+    }
+
+    pub fn set_signer_weights(ctx: Context<ValidatorConf>, weights: Vec<Validator>) -> Result<()> {
+        validator::set_signer_weights(ctx, weights)?;
+        Ok(())
+    }
+
+    pub fn add_signers(ctx: Context<AddSigners>, new_signers: Vec<ValidatorKey>) -> Result<()> {
         validator::add_signers(ctx, new_signers)?;
         Ok(())
     }
 
     pub fn remove_signers(
-        ctx: Context<BridgeConf>,
-        signers_to_remove: Vec<[u8; 20]>,
+        ctx: Context<RemoveSigners>,
+        signers_to_remove: Vec<ValidatorKey>,
     ) -> Result<()> {
         validator::remove_signers(ctx, signers_to_remove)?;
         Ok(())
     }
 
+    pub fn init_validator_epoch(ctx: Context<InitValidatorEpoch>) -> Result<()> {
+        validator::init_validator_epoch(ctx)?;
+        Ok(())
+    }
+
+    pub fn rotate_validators(
+        ctx: Context<RotateValidators>,
+        signatures: Vec<[u8; 65]>,
+    ) -> Result<()> {
+        validator::rotate_validators(ctx, signatures)?;
+        Ok(())
+    }
+
+    pub fn register_app(
+        ctx: Context<RegisterApp>,
+        name: String,
+        to_chains: Vec<Chain>,
+        mtypes: Vec<u8>,
+        fee_override: Option<u64>,
+    ) -> Result<()> {
+        app::register_app(ctx, name, to_chains, mtypes, fee_override)?;
+        Ok(())
+    }
+
+    pub fn update_app(
+        ctx: Context<AppConf>,
+        program: Pubkey,
+        name: String,
+        enabled: bool,
+        to_chains: Vec<Chain>,
+        mtypes: Vec<u8>,
+        fee_override: Option<u64>,
+    ) -> Result<()> {
+        app::update_app(ctx, program, name, enabled, to_chains, mtypes, fee_override)?;
+        Ok(())
+    }
+
+    pub fn remove_app(ctx: Context<RemoveApp>, program: Pubkey) -> Result<()> {
+        app::remove_app(ctx, program)?;
+        Ok(())
+    }
+
     pub fn set_bridge_fee(ctx: Context<BridgeConf>, bridge_fee: u64) -> Result<()> {
         message::set_bridge_fee(ctx, bridge_fee)?;
         Ok(())
     }
 
+    pub fn set_chain_fee(
+        ctx: Context<ChainFeeConf>,
+        chain: Chain,
+        base_fee: u64,
+        byte_fee: u64,
+    ) -> Result<()> {
+        message::set_chain_fee(ctx, chain, base_fee, byte_fee)?;
+        Ok(())
+    }
+
     pub fn init_to_chain_nonce_account(
         ctx: Context<InitSendToChainNonce>,
         to_chain: Chain,
+        from_addr: Pubkey,
+        to_addr: [u8; 32],
     ) -> Result<()> {
-        message::init_to_chain_nonce_account(ctx, to_chain)?;
+        message::init_to_chain_nonce_account(ctx, to_chain, from_addr, to_addr)?;
         Ok(())
     }
 
@@ -71,6 +200,23 @@ pub mod bridge_core {
         Ok(())
     }
 
+    pub fn quote_send(ctx: Context<QuoteSend>, to_chain: Chain, body_len: u32) -> Result<SendQuote> {
+        let quote = message::quote_send(ctx, to_chain, body_len)?;
+        Ok(quote)
+    }
+
+    pub fn send_message_with_token(
+        ctx: Context<SendToOtherChainWithToken>,
+        to_chain: Chain,
+        to_addr: [u8; 32],
+        mbody: Vec<u8>,
+        mtype: u8,
+        upload_fee: u64,
+    ) -> Result<()> {
+        message::send_message_with_token(ctx, to_chain, to_addr, mbody, mtype, upload_fee)?;
+        Ok(())
+    }
+
     pub fn confirm_message(
         ctx: Context<ConfirmFromOtherChain>,
         msg_header: MsgHeader,
@@ -82,8 +228,195 @@ pub mod bridge_core {
         Ok(())
     }
 
+    pub fn confirm_message_precompiled(
+        ctx: Context<ConfirmFromOtherChain>,
+        msg_header: MsgHeader,
+        msg_body: Vec<u8>,
+    ) -> Result<()> {
+        message::confirm_message_precompiled(ctx, msg_header, msg_body)?;
+        Ok(())
+    }
+
+    pub fn post_root(
+        ctx: Context<PostRoot>,
+        from_chain: Chain,
+        root: [u8; 32],
+        count: u64,
+        accum_pk: Vec<u8>,
+        signatures: Vec<[u8; 65]>,
+    ) -> Result<()> {
+        root::post_root(ctx, from_chain, root, count, accum_pk, signatures)?;
+        Ok(())
+    }
+
+    pub fn confirm_message_with_proof(
+        ctx: Context<ConfirmWithProof>,
+        msg_header: MsgHeader,
+        msg_body: Vec<u8>,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        root::confirm_message_with_proof(ctx, msg_header, msg_body, leaf_index, proof)?;
+        Ok(())
+    }
+
+    pub fn set_chain_verification(
+        ctx: Context<ChainVerificationConf>,
+        chain: Chain,
+        mode: ChainVerifyMode,
+        challenge_period: i64,
+        min_bond: u64,
+    ) -> Result<()> {
+        optimistic::set_chain_verification(ctx, chain, mode, challenge_period, min_bond)?;
+        Ok(())
+    }
+
+    pub fn post_attestation(
+        ctx: Context<PostAttestation>,
+        from_chain: Chain,
+        root: [u8; 32],
+        count: u64,
+        bond: u64,
+    ) -> Result<()> {
+        optimistic::post_attestation(ctx, from_chain, root, count, bond)?;
+        Ok(())
+    }
+
+    pub fn challenge_attestation(
+        ctx: Context<ChallengeAttestation>,
+        accum_pk: Vec<u8>,
+        signatures: Vec<[u8; 65]>,
+    ) -> Result<()> {
+        optimistic::challenge_attestation(ctx, accum_pk, signatures)?;
+        Ok(())
+    }
+
+    pub fn release_bond(ctx: Context<ReleaseBond>) -> Result<()> {
+        optimistic::release_bond(ctx)?;
+        Ok(())
+    }
+
+    pub fn confirm_message_optimistic(
+        ctx: Context<ConfirmOptimistic>,
+        msg_header: MsgHeader,
+        msg_body: Vec<u8>,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        optimistic::confirm_message_optimistic(ctx, msg_header, msg_body, leaf_index, proof)?;
+        Ok(())
+    }
+
+    pub fn verify_message(
+        ctx: Context<VerifyFromOtherChain>,
+        msg_header: MsgHeader,
+        msg_body: Vec<u8>,
+        accum_pk: Vec<u8>,
+        signatures: Vec<[u8; 65]>,
+    ) -> Result<()> {
+        pending::verify_message(ctx, msg_header, msg_body, accum_pk, signatures)?;
+        Ok(())
+    }
+
+    pub fn execute_message(ctx: Context<ExecuteMessage>) -> Result<()> {
+        pending::execute_message(ctx)?;
+        Ok(())
+    }
+
+    pub fn fail_message(ctx: Context<ExecuteMessage>) -> Result<()> {
+        pending::fail_message(ctx)?;
+        Ok(())
+    }
+
+    pub fn deliver_message<'info>(
+        ctx: Context<'_, '_, '_, 'info, DeliverMessage<'info>>,
+    ) -> Result<()> {
+        pending::deliver_message(ctx)?;
+        Ok(())
+    }
+
+    pub fn init_channel(
+        ctx: Context<InitChannel>,
+        from_chain: Chain,
+        from_addr: [u8; 32],
+        delivery_mode: DeliveryMode,
+    ) -> Result<()> {
+        message::init_channel(ctx, from_chain, from_addr, delivery_mode)?;
+        Ok(())
+    }
+
+    pub fn migrate_from_chain_nonce(
+        ctx: Context<MigrateFromChainNonce>,
+        from_chain: Chain,
+        from_addr: [u8; 32],
+    ) -> Result<()> {
+        message::migrate_from_chain_nonce(ctx, from_chain, from_addr)?;
+        Ok(())
+    }
+
+    pub fn migrate_to_chain_nonce(
+        ctx: Context<MigrateToChainNonce>,
+        to_chain: Chain,
+        from_addr: Pubkey,
+        to_addr: [u8; 32],
+    ) -> Result<()> {
+        message::migrate_to_chain_nonce(ctx, to_chain, from_addr, to_addr)?;
+        Ok(())
+    }
+
     pub fn withdraw_fee(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         message::withdraw_fee(ctx, amount)?;
         Ok(())
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_fee_split(
+        ctx: Context<FeeSplitConf>,
+        treasury: Pubkey,
+        treasury_bps: u16,
+        validator_bps: u16,
+        relayer_bps: u16,
+        validator_share: ValidatorShare,
+        reward_per_message: u64,
+    ) -> Result<()> {
+        fee::set_fee_split(
+            ctx,
+            treasury,
+            treasury_bps,
+            validator_bps,
+            relayer_bps,
+            validator_share,
+            reward_per_message,
+        )?;
+        Ok(())
+    }
+
+    pub fn claim_fee(ctx: Context<ClaimFee>) -> Result<()> {
+        fee::claim_fee(ctx)?;
+        Ok(())
+    }
+
+    pub fn claim_validator_fee(
+        ctx: Context<ClaimValidatorFee>,
+        epoch: u64,
+        index: u16,
+        signature: Option<[u8; 65]>,
+    ) -> Result<()> {
+        fee::claim_validator_fee(ctx, epoch, index, signature)?;
+        Ok(())
+    }
+
+    pub fn set_fee_mint(
+        ctx: Context<FeeMintConf>,
+        tokens_per_sol: u64,
+        enabled: bool,
+    ) -> Result<()> {
+        message::set_fee_mint(ctx, tokens_per_sol, enabled)?;
+        Ok(())
+    }
+
+    pub fn withdraw_token_fee(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+        message::withdraw_token_fee(ctx, amount)?;
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::merkle::{hash_pair, zero_hashes, MERKLE_DEPTH};
 use anchor_lang::prelude::*;
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::{collections::BTreeMap, str};
@@ -6,6 +7,32 @@ pub const CONFIG_SEED: &str = "global";
 pub const TO_NONCE_SEED: &str = "toNonce";
 pub const FROM_NONCE_SEED: &str = "fromNonce";
 pub const MESSAGE_FEE_SEED: &str = "vaultFee";
+pub const CHAIN_PAUSE_SEED: &str = "chainPause";
+pub const CHAIN_FEE_SEED: &str = "chainFee";
+pub const FEE_MINT_SEED: &str = "feeMint";
+pub const OUTBOUND_SEED: &str = "outbound";
+pub const OUTBOUND_RING_LEN: usize = 64;
+pub const OUTBOUND_TREE_SEED: &str = "outboundTree";
+pub const ROOT_RECORD_SEED: &str = "root";
+pub const CHAIN_VERIFICATION_SEED: &str = "chainVerify";
+pub const ATTESTATION_SEED: &str = "attestation";
+// an hour for the validators to answer a fraudulent attestation
+pub const MIN_CHALLENGE_PERIOD: i64 = 3600;
+pub const FEE_SPLIT_SEED: &str = "feeSplit";
+pub const FEE_BALANCE_SEED: &str = "feeBalance";
+pub const TREASURY_SEED: &str = "treasury";
+pub const VALIDATOR_REWARDS_SEED: &str = "validatorRewards";
+pub const MAX_FEE_BPS: u16 = 10_000;
+pub const VALIDATOR_SET_SEED: &str = "validatorSet";
+pub const MESSAGE_RECEIPT_SEED: &str = "receipt";
+pub const PENDING_MESSAGE_SEED: &str = "pending";
+pub const DELIVERY_AUTHORITY_SEED: &str = "delivery";
+pub const APP_REGISTRATION_SEED: &str = "app";
+
+pub const MAX_APP_NAME_LEN: usize = 32;
+pub const MAX_APP_CHAINS: usize = 16;
+pub const MAX_APP_MTYPES: usize = 16;
+pub const NONCE_WINDOW: u64 = 1024;
 
 #[cfg(feature = "mainnet")]
 pub const CHAIN_ID: u64 = 101;
@@ -21,6 +48,15 @@ pub enum ChainType {
     Solana,
 }
 
+/// How confirm_message checks the validators signed a message
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum VerifyMode {
+    /// One secp256k1/ed25519 signature per validator, summed against the quorum
+    Multisig,
+    /// A single Schnorr signature of the aggregate key of the active ValidatorSet
+    Aggregate,
+}
+
 #[derive(Debug, Clone, AnchorDeserialize, AnchorSerialize, PartialEq)]
 pub struct Chain {
     pub chain_type: u8,
@@ -57,6 +93,8 @@ pub struct MsgHeader {
     pub upload_gas_fee: u128,
 }
 impl MsgHeader {
+    pub const LEN: usize = 1 + 8 + Chain::LEN + 32 + Chain::LEN + 32 + 16;
+
     pub fn to_be_bytes(&self) -> Vec<u8> {
         [
             self.mtype.to_be_bytes().to_vec(),
@@ -75,27 +113,230 @@ impl MsgHeader {
 #[derive(Debug)]
 pub struct ConfigInfo {
     pub admin: Pubkey,
+    // legacy validator list, moved into the ValidatorSet of epoch 1 by migrate_validator_set
     pub signers: Vec<[u8; 20]>,
     pub threshold: u8,
     pub bridge_fee: u64,
     pub bump: u8,
-    pub padding: [u8; 136],
+    pub pending_admin: Pubkey,
+    pub guardian: Pubkey,
+    pub paused: bool,
+    pub epoch: u64,
+    pub verify_mode: VerifyMode,
+    pub padding: [u8; 62],
 }
 impl ConfigInfo {
-    pub const LEN: usize = 32 + 4 + 20 * 12 + 1 + 8 + 1 + 136;
+    pub const LEN: usize = 32 + 4 + 20 * 12 + 1 + 8 + 1 + 32 + 32 + 1 + 8 + 1 + 62;
     pub const SEEDS: &str = CONFIG_SEED;
 }
 
+/// Outbound channel from a source app on this chain to a destination app on `chain`
 #[account]
 pub struct ToChainNonce {
     pub chain: Chain,
     pub max_nonce: u64,
+    pub from_addr: [u8; 32],
+    pub to_addr: [u8; 32],
 }
 impl ToChainNonce {
-    pub const LEN: usize = 9 + 8;
+    pub const LEN: usize = 9 + 8 + 32 + 32;
     pub const SEED_SUFFIX: &str = TO_NONCE_SEED;
 }
 
+/// Legacy outbound counter of a chain, `[chain, TO_NONCE_SEED]`, shared by every app
+/// before channels were keyed by app. Seeds the channels in migrate_to_chain_nonce.
+#[derive(AnchorDeserialize)]
+pub struct LegacyToChainNonce {
+    pub chain: Chain,
+    pub max_nonce: u64,
+}
+impl LegacyToChainNonce {
+    /// Read the account, which carries the ToChainNonce discriminator
+    pub fn load(info: &AccountInfo) -> Result<Self> {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *ToChainNonce::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+#[account]
+pub struct ChainPause {
+    pub chain: Chain,
+    pub outbound_paused: bool,
+    pub inbound_paused: bool,
+}
+impl ChainPause {
+    pub const LEN: usize = 9 + 1 + 1;
+    pub const SEED_SUFFIX: &str = CHAIN_PAUSE_SEED;
+
+    // The pause account is only created once a chain is paused for the first time
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let chain_pause = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(chain_pause))
+    }
+}
+
+/// How the messages of a source chain are verified
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum ChainVerifyMode {
+    // validator signatures of every message or root
+    Validators,
+    // bonded attestations of roots, also accepting validator signatures
+    Optimistic,
+}
+
+/// Verification of the messages of one source chain, ChainVerifyMode::Validators
+/// until the admin configures it
+#[account]
+pub struct ChainVerification {
+    pub chain: Chain,
+    pub mode: ChainVerifyMode,
+    // seconds an attestation can be challenged before its messages are executable
+    pub challenge_period: i64,
+    // lamports an attester bonds with every attestation
+    pub min_bond: u64,
+}
+impl ChainVerification {
+    pub const LEN: usize = 9 + 1 + 8 + 8;
+    pub const SEED_SUFFIX: &str = CHAIN_VERIFICATION_SEED;
+
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let chain_verification = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(chain_verification))
+    }
+}
+
+/// Bridge fee of messages to one chain, `base_fee + byte_fee * mbody.len()` lamports
+#[account]
+pub struct ChainFee {
+    pub chain: Chain,
+    pub base_fee: u64,
+    pub byte_fee: u64,
+}
+impl ChainFee {
+    pub const LEN: usize = 9 + 8 + 8;
+    pub const SEED_SUFFIX: &str = CHAIN_FEE_SEED;
+
+    // Chains without a schedule are charged ConfigInfo.bridge_fee
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let chain_fee = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(chain_fee))
+    }
+
+    pub fn fee(&self, body_len: usize) -> Result<u64> {
+        self.byte_fee
+            .checked_mul(body_len as u64)
+            .and_then(|byte_fee| byte_fee.checked_add(self.base_fee))
+            .ok_or(crate::error::ErrorCode::FeeOverflow.into())
+    }
+}
+
+/// An SPL mint accepted for bridge fees, held in the fee vault owned by the vaultFee PDA
+#[account]
+pub struct FeeMint {
+    pub mint: Pubkey,
+    // token units charged per SOL of bridge fee
+    pub tokens_per_sol: u64,
+    pub enabled: bool,
+}
+impl FeeMint {
+    pub const LEN: usize = 32 + 8 + 1;
+    pub const SEED_SUFFIX: &str = FEE_MINT_SEED;
+
+    /// The bridge fee in token units, rounded up
+    pub fn token_fee(&self, lamports: u64) -> Result<u64> {
+        require!(self.enabled, crate::error::ErrorCode::FeeMintDisabled);
+        let token_fee = (lamports as u128 * self.tokens_per_sol as u128).div_ceil(1_000_000_000);
+        u64::try_from(token_fee).map_err(|_| crate::error::ErrorCode::FeeOverflow.into())
+    }
+}
+
+/// How the validator part of a message reward is shared
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum ValidatorShare {
+    // among the validators that signed the message, equally in VerifyMode::Aggregate
+    ProRata,
+    // among every validator of the set
+    Equal,
+}
+
+/// Split of the collected bridge fees. Every confirmed message moves `reward_per_message`
+/// lamports of the vaultFee balance into the claimable balances of the treasury, the
+/// validators and the relayer.
+#[account]
+pub struct FeeSplit {
+    pub treasury: Pubkey,
+    pub treasury_bps: u16,
+    pub validator_bps: u16,
+    pub relayer_bps: u16,
+    pub validator_share: ValidatorShare,
+    pub reward_per_message: u64,
+    // lamports of vaultFee owed to the claimable balances
+    pub allocated: u64,
+}
+impl FeeSplit {
+    pub const LEN: usize = 32 + 2 + 2 + 2 + 1 + 8 + 8;
+    pub const SEEDS: &str = FEE_SPLIT_SEED;
+
+    // The split is only created once the admin configures it
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let fee_split = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(fee_split))
+    }
+
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    pub fn check_bps(&self) -> Result<()> {
+        require!(
+            self.treasury_bps as u32 + self.validator_bps as u32 + self.relayer_bps as u32
+                == MAX_FEE_BPS as u32,
+            crate::error::ErrorCode::InvalidFeeSplit
+        );
+        Ok(())
+    }
+}
+
+/// Lamports of vaultFee claimable by `owner`, a relayer or the treasury
+#[account]
+pub struct FeeBalance {
+    pub owner: Pubkey,
+    pub claimable: u64,
+}
+impl FeeBalance {
+    pub const LEN: usize = 32 + 8;
+    pub const SEED_SUFFIX: &str = FEE_BALANCE_SEED;
+}
+
+/// Returned by quote_send
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub struct SendQuote {
+    // lamports send_message charges on top of the upload fee
+    pub bridge_fee: u64,
+}
+
+/// Legacy replay window of a receiver, a bincode BTreeMap of the last 50 consumed nonces.
+/// Merged into the FromChainNonceWindow of an app channel by migrate_from_chain_nonce.
 #[account]
 pub struct FromChainNonce {
     pub chain: Chain,
@@ -107,7 +348,7 @@ impl FromChainNonce {
     pub const SEED_SUFFIX: &str = FROM_NONCE_SEED;
 
     // Convert Vec<u8> to BTreeMap<u64, ()>
-    fn vec_to_btreemap(&self) -> Result<BTreeMap<u64, ()>> {
+    pub fn vec_to_btreemap(&self) -> Result<BTreeMap<u64, ()>> {
         if self.missing_nonces.is_empty() {
             msg!("missing_nonces is empty");
             return Ok(BTreeMap::new());
@@ -122,38 +363,392 @@ impl FromChainNonce {
             }
         }
     }
+}
+
+/// How a channel accepts the nonces of a chain
+#[derive(Clone, Copy, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum DeliveryMode {
+    /// Any undelivered nonce, in any order
+    Unordered,
+    /// Only `last_nonce + 1`
+    Ordered,
+}
+
+/// Inbound channel from a source app on a chain to a destination app on this chain,
+/// a sliding bitmap of the delivered nonces `NONCE_WINDOW` nonces wide.
+///
+/// Bit `i` of `bitmap` (word `i / 64`, bit `i % 64`) is set once nonce `base_nonce + i`
+/// is delivered. Out of order arrival is handled as:
+/// - a nonce inside the window is accepted once, a second delivery is NonceConsumed
+/// - a nonce past the window slides it forward until the nonce is its last slot,
+///   the nonces sliding out are forgotten
+/// - a nonce below the window is accepted, its MessageReceipt still rejects replays,
+///   unless it is at most `legacy_ceiling`: those predate the receipts and can not be
+///   told apart from consumed ones, so they are NonceInvalid
+///
+/// An Ordered channel additionally rejects every nonce but `last_nonce + 1`.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct FromChainNonceWindow {
+    pub chain_id: u64,
+    pub last_nonce: u64,
+    pub base_nonce: u64,
+    pub legacy_ceiling: u64,
+    pub bitmap: [u64; 16],
+    pub from_addr: [u8; 32],
+    pub to_addr: [u8; 32],
+    pub chain_type: u8,
+    // DeliveryMode as u8
+    pub delivery_mode: u8,
+    pub padding: [u8; 6],
+}
+impl FromChainNonceWindow {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 16 * 8 + 32 + 32 + 1 + 1 + 6;
+    pub const SEED_SUFFIX: &str = FROM_NONCE_SEED;
+
+    pub fn chain(&self) -> Chain {
+        Chain {
+            chain_type: self.chain_type,
+            chain_id: self.chain_id,
+        }
+    }
+
+    /// Rebuild the window of a legacy account, every nonce up to its last nonce is legacy
+    pub fn from_legacy(legacy: &FromChainNonce, from_addr: [u8; 32], to_addr: [u8; 32]) -> Result<Self> {
+        let mut window = Self {
+            chain_id: legacy.chain.chain_id,
+            last_nonce: 0,
+            base_nonce: 0,
+            legacy_ceiling: 0,
+            bitmap: [0; 16],
+            from_addr,
+            to_addr,
+            chain_type: legacy.chain.chain_type,
+            delivery_mode: DeliveryMode::Unordered as u8,
+            padding: [0; 6],
+        };
+        window.merge_legacy(legacy)?;
+        Ok(window)
+    }
+
+    /// Fold a legacy account into the window. The legacy accounts were per receiver,
+    /// so the windows of several receivers are merged one after the other: the window
+    /// rejects what any of them rejects, `legacy_ceiling` being the highest legacy
+    /// last nonce and the consumed nonces of each being marked delivered.
+    pub fn merge_legacy(&mut self, legacy: &FromChainNonce) -> Result<()> {
+        let consumed = legacy.vec_to_btreemap()?;
+        // a legacy window rejects every nonce below its oldest consumed one
+        let floor = match consumed.keys().next() {
+            Some(min_nonce) => *min_nonce,
+            None => legacy.last_nonce + 1,
+        };
+        self.legacy_ceiling = self.legacy_ceiling.max(legacy.last_nonce);
+        if floor > self.base_nonce {
+            self.move_base(floor);
+        }
+        for nonce in consumed.keys() {
+            if *nonce >= self.base_nonce && !self.is_delivered(*nonce) {
+                self.deliver(*nonce)?;
+            }
+        }
+        self.last_nonce = self.last_nonce.max(legacy.last_nonce);
+        Ok(())
+    }
 
-    // Convert BTreeMap<u64, ()> to Vec<u8>
-    fn btreemap_to_vec(map: &BTreeMap<u64, ()>) -> Result<Vec<u8>> {
-        let data = bincode::serialize(map).unwrap();
-        Ok(data)
+    pub fn delivery_mode(&self) -> DeliveryMode {
+        if self.delivery_mode == DeliveryMode::Ordered as u8 {
+            DeliveryMode::Ordered
+        } else {
+            DeliveryMode::Unordered
+        }
     }
 
-    pub fn check_and_store_nonce(&mut self, nonce: u64) -> Result<bool> {
-        let mut map = self.vec_to_btreemap()?;
-    
-        if map.is_empty() {
-            map.insert(nonce, ());
-            self.missing_nonces = Self::btreemap_to_vec(&map)?;
-            return Ok(true);
+    pub fn is_delivered(&self, nonce: u64) -> bool {
+        if nonce < self.base_nonce || nonce - self.base_nonce >= NONCE_WINDOW {
+            return false;
         }
-    
-        if map.contains_key(&nonce) {
-            return Err(crate::error::ErrorCode::NonceConsumed.into());
+        let offset = nonce - self.base_nonce;
+        self.bitmap[(offset / 64) as usize] & (1 << (offset % 64)) != 0
+    }
+
+    /// Mark a nonce as delivered, see the type docs for the accept/reject rules
+    pub fn record(&mut self, nonce: u64) -> Result<()> {
+        if self.delivery_mode() == DeliveryMode::Ordered {
+            require!(
+                nonce == self.last_nonce + 1,
+                crate::error::ErrorCode::NonceOutOfOrder
+            );
+        }
+        if nonce < self.base_nonce {
+            require!(
+                nonce > self.legacy_ceiling,
+                crate::error::ErrorCode::NonceInvalid
+            );
+            return Ok(());
+        }
+        self.deliver(nonce)
+    }
+
+    // Set the bit of a nonce at or above the window, sliding the window when past it
+    fn deliver(&mut self, nonce: u64) -> Result<()> {
+        if nonce - self.base_nonce >= NONCE_WINDOW {
+            self.move_base(nonce - (NONCE_WINDOW - 1));
         }
-    
-        let min_nonce = *map.keys().next().ok_or(crate::error::ErrorCode::NonceInvalid)?;
-    
-        if nonce < min_nonce {
-            return Err(crate::error::ErrorCode::NonceInvalid.into());
+
+        require!(
+            !self.is_delivered(nonce),
+            crate::error::ErrorCode::NonceConsumed
+        );
+        let offset = nonce - self.base_nonce;
+        self.bitmap[(offset / 64) as usize] |= 1 << (offset % 64);
+        self.last_nonce = self.last_nonce.max(nonce);
+        Ok(())
+    }
+
+    // Slide the window forward so it starts at `base_nonce`
+    fn move_base(&mut self, base_nonce: u64) {
+        self.slide(base_nonce - self.base_nonce);
+        self.base_nonce = base_nonce;
+    }
+
+    // Move bit `i` to bit `i - by`, dropping the bits below zero
+    fn slide(&mut self, by: u64) {
+        if by >= NONCE_WINDOW {
+            self.bitmap = [0; 16];
+            return;
         }
-    
-        if map.len() >= 50 {
-            map.remove(&min_nonce);
+        let words = (by / 64) as usize;
+        let bits = (by % 64) as u32;
+        let len = self.bitmap.len();
+        for i in 0..len {
+            let low = self.bitmap.get(i + words).copied().unwrap_or(0);
+            let high = self.bitmap.get(i + words + 1).copied().unwrap_or(0);
+            self.bitmap[i] = if bits == 0 {
+                low
+            } else {
+                (low >> bits) | (high << (64 - bits))
+            };
         }
-    
-        map.insert(nonce, ());
-        self.missing_nonces = Self::btreemap_to_vec(&map)?;
-        Ok(true)
+    }
+}
+
+/// Commitment of a sent message, `message_hash` as in utils::digest::message_hash
+#[zero_copy]
+#[derive(Debug)]
+pub struct OutboundCommitment {
+    pub message_hash: [u8; 32],
+    pub nonce: u64,
+    pub slot: u64,
+}
+
+/// The last OUTBOUND_RING_LEN messages sent on a channel, the message of nonce `n` is
+/// committed in entry `n % OUTBOUND_RING_LEN` until nonce `n + OUTBOUND_RING_LEN` is sent
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct OutboundRing {
+    pub chain_id: u64,
+    pub from_addr: [u8; 32],
+    pub to_addr: [u8; 32],
+    pub chain_type: u8,
+    pub padding: [u8; 7],
+    pub entries: [OutboundCommitment; OUTBOUND_RING_LEN],
+}
+impl OutboundRing {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 7 + 48 * OUTBOUND_RING_LEN;
+    pub const SEED_SUFFIX: &str = OUTBOUND_SEED;
+
+    pub fn record(&mut self, nonce: u64, message_hash: [u8; 32], slot: u64) {
+        self.entries[(nonce % OUTBOUND_RING_LEN as u64) as usize] = OutboundCommitment {
+            message_hash,
+            nonce,
+            slot,
+        };
+    }
+
+    /// The commitment of `nonce`, if it was not overwritten yet
+    pub fn get(&self, nonce: u64) -> Option<&OutboundCommitment> {
+        let entry = &self.entries[(nonce % OUTBOUND_RING_LEN as u64) as usize];
+        (nonce != 0 && entry.nonce == nonce).then_some(entry)
+    }
+}
+
+/// Incremental Merkle tree (utils::merkle) of every message sent to a chain, keeping
+/// only the left siblings of the next leaf, the remote chain attests to `root`
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct OutboundTree {
+    pub chain_id: u64,
+    pub count: u64,
+    pub root: [u8; 32],
+    pub branch: [[u8; 32]; MERKLE_DEPTH],
+    pub chain_type: u8,
+    pub padding: [u8; 7],
+}
+impl OutboundTree {
+    pub const LEN: usize = 8 + 8 + 32 + 32 * MERKLE_DEPTH + 1 + 7;
+    pub const SEED_SUFFIX: &str = OUTBOUND_TREE_SEED;
+
+    /// Append `leaf` and update the root, returns the index of the leaf
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.count;
+        require!(
+            index >> MERKLE_DEPTH == 0,
+            crate::error::ErrorCode::MerkleTreeFull
+        );
+        let mut node = leaf;
+        let mut size = index;
+        for branch in self.branch.iter_mut() {
+            if size & 1 == 0 {
+                *branch = node;
+                break;
+            }
+            node = hash_pair(branch, &node);
+            size >>= 1;
+        }
+        self.count += 1;
+
+        let mut node = [0u8; 32];
+        let mut size = self.count;
+        for (branch, zero) in self.branch.iter().zip(zero_hashes().iter()) {
+            node = if size & 1 == 1 {
+                hash_pair(branch, &node)
+            } else {
+                hash_pair(&node, zero)
+            };
+            size >>= 1;
+        }
+        self.root = node;
+        Ok(index)
+    }
+}
+
+/// Merkle root (utils::merkle) of the first `count` messages `from_chain` sent to this
+/// chain, signed by the validators of `epoch`. Messages under it are confirmed by
+/// inclusion proof instead of signatures.
+#[account]
+pub struct RootRecord {
+    pub from_chain: Chain,
+    pub root: [u8; 32],
+    pub count: u64,
+    pub epoch: u64,
+    pub poster: Pubkey,
+    pub posted_at: i64,
+}
+impl RootRecord {
+    pub const LEN: usize = Chain::LEN + 32 + 8 + 8 + 32 + 8;
+    pub const SEED_SUFFIX: &str = ROOT_RECORD_SEED;
+}
+
+/// Root of the first `count` messages `from_chain` sent to this chain, posted by a bonded
+/// attester of a ChainVerifyMode::Optimistic chain. Its messages are confirmed by
+/// inclusion proof once `challenge_ends_at` passed, unless the validators signed a fraud
+/// claim against it before, which cancels it and slashes the bond held in this account.
+#[account]
+pub struct Attestation {
+    pub from_chain: Chain,
+    pub root: [u8; 32],
+    pub count: u64,
+    pub attester: Pubkey,
+    pub bond: u64,
+    pub posted_at: i64,
+    pub challenge_ends_at: i64,
+    pub cancelled: bool,
+}
+impl Attestation {
+    pub const LEN: usize = Chain::LEN + 32 + 8 + 32 + 8 + 8 + 8 + 1;
+    pub const SEED_SUFFIX: &str = ATTESTATION_SEED;
+}
+
+/// Created when a message is confirmed, so the same message can never be confirmed twice
+#[account]
+pub struct MessageReceipt {
+    pub message_hash: [u8; 32],
+    pub from_chain: Chain,
+    pub nonce: u64,
+    pub executor: Pubkey,
+    pub executed_at: i64,
+    pub slot: u64,
+}
+impl MessageReceipt {
+    pub const LEN: usize = 32 + 9 + 8 + 32 + 8 + 8;
+    pub const SEED_SUFFIX: &str = MESSAGE_RECEIPT_SEED;
+}
+
+#[derive(Clone, Copy, Debug, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum MessageStatus {
+    Verified,
+    Executed,
+    Failed,
+}
+
+/// A verified message stored until its destination app executes it, so execution can
+/// be retried without the signatures being verified again
+#[account]
+pub struct PendingMessage {
+    pub message_hash: [u8; 32],
+    pub header: MsgHeader,
+    pub body: Vec<u8>,
+    pub status: MessageStatus,
+    pub verifier: Pubkey,
+    pub verified_at: i64,
+    pub executor: Pubkey,
+    pub executed_at: i64,
+    pub attempts: u32,
+}
+impl PendingMessage {
+    pub const SEED_SUFFIX: &str = PENDING_MESSAGE_SEED;
+
+    pub const fn space(body_len: usize) -> usize {
+        32 + MsgHeader::LEN + 4 + body_len + 1 + 32 + 8 + 32 + 8 + 4
+    }
+}
+
+/// An app allowed to send messages, registered by the admin
+#[account]
+pub struct AppRegistration {
+    pub program: Pubkey,
+    pub name: String,
+    pub enabled: bool,
+    pub to_chains: Vec<Chain>,
+    pub mtypes: Vec<u8>,
+    // flat bridge fee of the messages of the app, replacing both the ChainFee schedule
+    // of the destination chain and ConfigInfo.bridge_fee
+    pub fee_override: Option<u64>,
+}
+impl AppRegistration {
+    pub const LEN: usize = 32
+        + 4
+        + MAX_APP_NAME_LEN
+        + 1
+        + 4
+        + Chain::LEN * MAX_APP_CHAINS
+        + 4
+        + MAX_APP_MTYPES
+        + 1
+        + 8;
+    pub const SEED_SUFFIX: &str = APP_REGISTRATION_SEED;
+
+    pub fn check_limits(&self) -> Result<()> {
+        require!(
+            self.name.len() <= MAX_APP_NAME_LEN
+                && self.to_chains.len() <= MAX_APP_CHAINS
+                && self.mtypes.len() <= MAX_APP_MTYPES,
+            crate::error::ErrorCode::AppRegistryLimitExceeded
+        );
+        Ok(())
+    }
+
+    /// Check the app may send a message of `mtype` to `to_chain`
+    pub fn check_send(&self, to_chain: &Chain, mtype: u8) -> Result<()> {
+        require!(self.enabled, crate::error::ErrorCode::AppDisabled);
+        require!(self.to_chains.contains(to_chain), crate::error::ErrorCode::AppChainNotAllowed);
+        require!(self.mtypes.contains(&mtype), crate::error::ErrorCode::AppMtypeNotAllowed);
+        Ok(())
+    }
+
+    /// The bridge fee of a message of the app, `chain_fee` being the fee the ChainFee
+    /// schedule, or ConfigInfo.bridge_fee without one, charges it
+    pub fn message_fee(&self, chain_fee: u64) -> u64 {
+        self.fee_override.unwrap_or(chain_fee)
     }
 }
@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+
+use super::config::{CHAIN_ID, VALIDATOR_REWARDS_SEED, VALIDATOR_SET_SEED};
+use crate::utils::digest::{typed_claim, typed_rotation};
+
+pub const MAX_VALIDATORS: usize = 64;
+pub const MAX_QUORUM_BPS: u16 = 10_000;
+
+/// The key a validator signs with, an eth address checked by secp256k1 recovery
+/// or an ed25519 public key checked by the Ed25519Program
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum ValidatorKey {
+    Eth([u8; 20]),
+    Ed25519([u8; 32]),
+}
+impl ValidatorKey {
+    pub const LEN: usize = 1 + 32;
+
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        match self {
+            ValidatorKey::Eth(address) => [vec![0], address.to_vec()].concat(),
+            ValidatorKey::Ed25519(pubkey) => [vec![1], pubkey.to_vec()].concat(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub struct Validator {
+    pub signer: ValidatorKey,
+    pub weight: u64,
+}
+impl Validator {
+    pub const LEN: usize = ValidatorKey::LEN + 8;
+
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        [self.signer.to_be_bytes(), self.weight.to_be_bytes().to_vec()].concat()
+    }
+}
+
+/// The signed weight a message needs, either absolute or as a fraction of the total weight
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub enum Quorum {
+    Weight(u64),
+    Bps(u16),
+}
+impl Quorum {
+    pub const LEN: usize = 1 + 8;
+
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        match self {
+            Quorum::Weight(weight) => [vec![0], weight.to_be_bytes().to_vec()].concat(),
+            Quorum::Bps(bps) => [vec![1], bps.to_be_bytes().to_vec()].concat(),
+        }
+    }
+}
+
+/// A validator as stored after its ValidatorSet, `key` holding an eth address in its
+/// first 20 bytes or an ed25519 public key
+#[zero_copy]
+#[derive(Debug)]
+pub struct ValidatorEntry {
+    pub weight: u64,
+    pub key: [u8; 32],
+    // 0 for ValidatorKey::Eth, 1 for ValidatorKey::Ed25519
+    pub key_type: u8,
+    pub padding: [u8; 7],
+}
+impl ValidatorEntry {
+    pub const LEN: usize = 8 + 32 + 1 + 7;
+
+    pub fn new(validator: &Validator) -> Self {
+        let mut key = [0u8; 32];
+        let key_type = match &validator.signer {
+            ValidatorKey::Eth(address) => {
+                key[..20].copy_from_slice(address);
+                0
+            }
+            ValidatorKey::Ed25519(pubkey) => {
+                key.copy_from_slice(pubkey);
+                1
+            }
+        };
+        Self {
+            weight: validator.weight,
+            key,
+            key_type,
+            padding: [0; 7],
+        }
+    }
+
+    pub fn signer(&self) -> ValidatorKey {
+        if self.key_type == 0 {
+            let mut address = [0u8; 20];
+            address.copy_from_slice(&self.key[..20]);
+            ValidatorKey::Eth(address)
+        } else {
+            ValidatorKey::Ed25519(self.key)
+        }
+    }
+
+    pub fn is(&self, signer: &ValidatorKey) -> bool {
+        match signer {
+            ValidatorKey::Eth(address) => self.key_type == 0 && self.key[..20] == address[..],
+            ValidatorKey::Ed25519(pubkey) => self.key_type == 1 && self.key == *pubkey,
+        }
+    }
+
+    pub fn validator(&self) -> Validator {
+        Validator {
+            signer: self.signer(),
+            weight: self.weight,
+        }
+    }
+}
+
+/// The validator set of one epoch. The set of `ConfigInfo.epoch + 1` is the staged
+/// set, edited by the admin and activated by init_validator_epoch or rotate_validators.
+/// Its `count` validators follow it in the account data as ValidatorEntry, read in
+/// place through ValidatorSetRef.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct ValidatorSet {
+    pub epoch: u64,
+    // the weight of Quorum::Weight or the bps of Quorum::Bps
+    pub quorum_value: u64,
+    // compressed secp256k1 key the set threshold-signs with in VerifyMode::Aggregate,
+    // zero when the set has none
+    pub aggregate_pk: [u8; 33],
+    // 0 for Quorum::Weight, 1 for Quorum::Bps
+    pub quorum_type: u8,
+    pub count: u8,
+    pub padding: [u8; 5],
+}
+impl ValidatorSet {
+    pub const LEN: usize = 8 + 8 + 33 + 1 + 1 + 5;
+    pub const SEED_SUFFIX: &str = VALIDATOR_SET_SEED;
+
+    pub const fn space(validators: usize) -> usize {
+        Self::LEN + ValidatorEntry::LEN * validators
+    }
+
+    pub fn quorum(&self) -> Quorum {
+        if self.quorum_type == 0 {
+            Quorum::Weight(self.quorum_value)
+        } else {
+            Quorum::Bps(self.quorum_value as u16)
+        }
+    }
+
+    pub fn set_quorum(&mut self, quorum: &Quorum) {
+        (self.quorum_type, self.quorum_value) = match quorum {
+            Quorum::Weight(weight) => (0, *weight),
+            Quorum::Bps(bps) => (1, *bps as u64),
+        };
+    }
+
+    /// Write `validators` after the set in the data of its account, sized for them
+    pub fn store_validators(data: &mut [u8], validators: &[Validator]) -> Result<()> {
+        let (header, entries) = data[8..].split_at_mut(Self::LEN);
+        let set: &mut ValidatorSet = bytemuck::try_from_bytes_mut(header)
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        set.count = validators.len() as u8;
+        let entries: &mut [ValidatorEntry] =
+            bytemuck::try_cast_slice_mut(&mut entries[..ValidatorEntry::LEN * validators.len()])
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        for (entry, validator) in entries.iter_mut().zip(validators) {
+            *entry = ValidatorEntry::new(validator);
+        }
+        Ok(())
+    }
+
+    /// The typed data (see utils::digest) the current validators sign to hand over to the
+    /// next epoch, bound to the program id, `CHAIN_ID` and both epoch numbers so signatures
+    /// of a retired set or of another deployment cannot be replayed
+    pub fn rotation_payload(current_epoch: u64, new_set: &ValidatorSetRef) -> Vec<u8> {
+        typed_rotation(&crate::ID, CHAIN_ID, current_epoch, new_set)
+    }
+}
+
+/// A ValidatorSet and its validators borrowed from the account data, without
+/// deserializing or copying the set
+#[derive(Clone, Copy)]
+pub struct ValidatorSetRef<'a> {
+    pub set: &'a ValidatorSet,
+    pub validators: &'a [ValidatorEntry],
+}
+impl<'a> ValidatorSetRef<'a> {
+    /// Borrow the set from the data of a ValidatorSet account
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        require!(
+            data.len() >= 8 + ValidatorSet::LEN && data[..8] == *ValidatorSet::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        let (header, entries) = data[8..].split_at(ValidatorSet::LEN);
+        let set: &ValidatorSet =
+            bytemuck::try_from_bytes(header).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        let entries = entries
+            .get(..ValidatorEntry::LEN * set.count as usize)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        let validators =
+            bytemuck::try_cast_slice(entries).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        Ok(Self { set, validators })
+    }
+
+    pub fn to_validators(&self) -> Vec<Validator> {
+        self.validators.iter().map(ValidatorEntry::validator).collect()
+    }
+
+    pub fn index_of(&self, signer: &ValidatorKey) -> Option<usize> {
+        self.validators.iter().position(|v| v.is(signer))
+    }
+
+    pub fn total_weight(&self) -> u128 {
+        self.validators.iter().map(|v| v.weight as u128).sum()
+    }
+
+    pub fn weight_of(&self, signer: &ValidatorKey) -> Option<u64> {
+        self.index_of(signer).map(|index| self.validators[index].weight)
+    }
+
+    /// The summed weight of signers needed to reach the quorum
+    pub fn required_weight(&self) -> u128 {
+        match self.set.quorum() {
+            Quorum::Weight(weight) => weight as u128,
+            Quorum::Bps(bps) => {
+                (self.total_weight() * bps as u128).div_ceil(MAX_QUORUM_BPS as u128)
+            }
+        }
+    }
+
+    /// Check that the unique, known signers reach the quorum by summed weight
+    pub fn verify_quorum(&self, signers: &[ValidatorKey]) -> Result<()> {
+        let mut counted: Vec<&ValidatorKey> = Vec::with_capacity(signers.len());
+        let mut signed_weight: u128 = 0;
+        for signer in signers {
+            let weight = self
+                .weight_of(signer)
+                .ok_or(crate::error::ErrorCode::SignaturePublicKeyMismatch)?;
+            if !counted.contains(&signer) {
+                counted.push(signer);
+                signed_weight += weight as u128;
+            }
+        }
+        require!(
+            signed_weight >= self.required_weight(),
+            crate::error::ErrorCode::SignaturesLess
+        );
+        Ok(())
+    }
+
+    pub fn check_quorum(&self) -> Result<()> {
+        let total_weight = self.total_weight();
+        let valid = match self.set.quorum() {
+            Quorum::Weight(weight) => weight > 0 && weight as u128 <= total_weight,
+            Quorum::Bps(bps) => bps > 0 && bps <= MAX_QUORUM_BPS && total_weight > 0,
+        };
+        if !valid {
+            return Err(crate::error::ErrorCode::InvalidThreshold.into());
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for ValidatorSetRef<'_> {
+    type Target = ValidatorSet;
+
+    fn deref(&self) -> &ValidatorSet {
+        self.set
+    }
+}
+
+/// Lamports of vaultFee claimable by the validators of one epoch,
+/// in the order of the validators of their ValidatorSet
+#[account]
+pub struct ValidatorRewards {
+    pub epoch: u64,
+    pub claimable: Vec<u64>,
+}
+impl ValidatorRewards {
+    pub const SEED_SUFFIX: &str = VALIDATOR_REWARDS_SEED;
+
+    pub const fn space(validators: usize) -> usize {
+        8 + 4 + 8 * validators
+    }
+
+    /// The typed data (utils::digest) an eth validator signs to claim its rewards to `recipient`
+    pub fn claim_payload(epoch: u64, index: u16, recipient: &Pubkey) -> Vec<u8> {
+        typed_claim(&crate::ID, CHAIN_ID, epoch, index, recipient)
+    }
+}
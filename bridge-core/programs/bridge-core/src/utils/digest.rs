@@ -0,0 +1,224 @@
+//! EIP-712 style typed data the validators sign for inbound messages, message roots,
+//! fraud claims against attested roots, validator rotations and validator fee claims,
+//! bound to the program id, `CHAIN_ID` and validator epoch. Usable off-chain to build
+//! the same bytes.
+use crate::state::{
+    config::{Chain, MsgHeader},
+    validator::ValidatorSetRef,
+};
+use anchor_lang::{prelude::*, solana_program::keccak::hashv};
+
+pub const DOMAIN_NAME: &str = "bridge-core";
+pub const DOMAIN_VERSION: &str = "1";
+pub const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,bytes32 verifyingContract)";
+pub const MESSAGE_TYPE: &str = "Message(uint8 mtype,uint64 nonce,uint8 fromChainType,uint64 fromChainId,bytes32 fromAddr,uint8 toChainType,uint64 toChainId,bytes32 toAddr,uint128 uploadGasFee,uint64 epoch,bytes body)";
+pub const ROOT_TYPE: &str =
+    "Root(uint8 fromChainType,uint64 fromChainId,bytes32 root,uint64 count,uint64 epoch)";
+pub const ROTATION_TYPE: &str =
+    "Rotation(uint64 currentEpoch,uint64 newEpoch,bytes32 validators,bytes aggregateKey)";
+pub const FRAUD_TYPE: &str =
+    "Fraud(uint8 fromChainType,uint64 fromChainId,bytes32 root,uint64 count,uint64 epoch,bytes32 challenger)";
+pub const CLAIM_TYPE: &str = "Claim(uint64 epoch,uint16 index,bytes32 recipient)";
+
+/// Epoch independent id of a message, `keccak(header || body)`, seeding its MessageReceipt
+pub fn message_hash(msg_header: &MsgHeader, msg_body: &[u8]) -> [u8; 32] {
+    hashv(&[&msg_header.to_be_bytes(), msg_body]).to_bytes()
+}
+
+/// Left pad a big endian integer to a 32 byte word
+fn word(be_bytes: &[u8]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[32 - be_bytes.len()..].copy_from_slice(be_bytes);
+    word
+}
+
+/// keccak(DOMAIN_TYPE) || keccak(name) || keccak(version) || chainId || programId
+pub fn domain_separator(program_id: &Pubkey, chain_id: u64) -> [u8; 32] {
+    hashv(&[
+        &hashv(&[DOMAIN_TYPE.as_bytes()]).to_bytes(),
+        &hashv(&[DOMAIN_NAME.as_bytes()]).to_bytes(),
+        &hashv(&[DOMAIN_VERSION.as_bytes()]).to_bytes(),
+        &word(&chain_id.to_be_bytes()),
+        &program_id.to_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// The EIP-712 hashStruct of a message, every field encoded as a 32 byte word
+pub fn message_struct_hash(msg_header: &MsgHeader, msg_body: &[u8], epoch: u64) -> [u8; 32] {
+    hashv(&[
+        &hashv(&[MESSAGE_TYPE.as_bytes()]).to_bytes(),
+        &word(&msg_header.mtype.to_be_bytes()),
+        &word(&msg_header.nonce.to_be_bytes()),
+        &word(&msg_header.from_chain.chain_type.to_be_bytes()),
+        &word(&msg_header.from_chain.chain_id.to_be_bytes()),
+        &msg_header.from_addr,
+        &word(&msg_header.to_chain.chain_type.to_be_bytes()),
+        &word(&msg_header.to_chain.chain_id.to_be_bytes()),
+        &msg_header.to_addr,
+        &word(&msg_header.upload_gas_fee.to_be_bytes()),
+        &word(&epoch.to_be_bytes()),
+        &hashv(&[msg_body]).to_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// `0x19 0x01 || domainSeparator || hashStruct(message)`, the bytes handed to the
+/// secp256k1 and ed25519 signers
+pub fn typed_message(
+    program_id: &Pubkey,
+    chain_id: u64,
+    msg_header: &MsgHeader,
+    msg_body: &[u8],
+    epoch: u64,
+) -> Vec<u8> {
+    [
+        &[0x19, 0x01][..],
+        &domain_separator(program_id, chain_id),
+        &message_struct_hash(msg_header, msg_body, epoch),
+    ]
+    .concat()
+}
+
+/// The EIP-712 digest, keccak of the typed message
+pub fn message_digest(
+    program_id: &Pubkey,
+    chain_id: u64,
+    msg_header: &MsgHeader,
+    msg_body: &[u8],
+    epoch: u64,
+) -> [u8; 32] {
+    hashv(&[&typed_message(program_id, chain_id, msg_header, msg_body, epoch)]).to_bytes()
+}
+
+/// The EIP-712 hashStruct of the Merkle root (utils::merkle) of the first `count`
+/// messages `from_chain` sent to this chain
+pub fn root_struct_hash(from_chain: &Chain, root: &[u8; 32], count: u64, epoch: u64) -> [u8; 32] {
+    hashv(&[
+        &hashv(&[ROOT_TYPE.as_bytes()]).to_bytes(),
+        &word(&from_chain.chain_type.to_be_bytes()),
+        &word(&from_chain.chain_id.to_be_bytes()),
+        root,
+        &word(&count.to_be_bytes()),
+        &word(&epoch.to_be_bytes()),
+    ])
+    .to_bytes()
+}
+
+/// The EIP-712 hashStruct of a claim that an attested root is not the root of the
+/// messages `from_chain` sent, only `challenger` can submit it and take the bond
+pub fn fraud_struct_hash(
+    from_chain: &Chain,
+    root: &[u8; 32],
+    count: u64,
+    epoch: u64,
+    challenger: &Pubkey,
+) -> [u8; 32] {
+    hashv(&[
+        &hashv(&[FRAUD_TYPE.as_bytes()]).to_bytes(),
+        &word(&from_chain.chain_type.to_be_bytes()),
+        &word(&from_chain.chain_id.to_be_bytes()),
+        root,
+        &word(&count.to_be_bytes()),
+        &word(&epoch.to_be_bytes()),
+        challenger.as_ref(),
+    ])
+    .to_bytes()
+}
+
+/// `0x19 0x01 || domainSeparator || hashStruct(root)`, signed like a typed message
+pub fn typed_root(
+    program_id: &Pubkey,
+    chain_id: u64,
+    from_chain: &Chain,
+    root: &[u8; 32],
+    count: u64,
+    epoch: u64,
+) -> Vec<u8> {
+    [
+        &[0x19, 0x01][..],
+        &domain_separator(program_id, chain_id),
+        &root_struct_hash(from_chain, root, count, epoch),
+    ]
+    .concat()
+}
+
+/// `0x19 0x01 || domainSeparator || hashStruct(fraud)`, signed like a typed message
+pub fn typed_fraud(
+    program_id: &Pubkey,
+    chain_id: u64,
+    from_chain: &Chain,
+    root: &[u8; 32],
+    count: u64,
+    epoch: u64,
+    challenger: &Pubkey,
+) -> Vec<u8> {
+    [
+        &[0x19, 0x01][..],
+        &domain_separator(program_id, chain_id),
+        &fraud_struct_hash(from_chain, root, count, epoch, challenger),
+    ]
+    .concat()
+}
+
+/// The EIP-712 hashStruct of a rotation from `current_epoch` to `new_set`,
+/// `validators` being keccak of the encoded quorum and validators of the new set and
+/// `aggregateKey` its VerifyMode::Aggregate key
+pub fn rotation_struct_hash(current_epoch: u64, new_set: &ValidatorSetRef) -> [u8; 32] {
+    let mut validators = new_set.quorum().to_be_bytes();
+    for validator in new_set.validators.iter() {
+        validators.extend(validator.validator().to_be_bytes());
+    }
+    hashv(&[
+        &hashv(&[ROTATION_TYPE.as_bytes()]).to_bytes(),
+        &word(&current_epoch.to_be_bytes()),
+        &word(&new_set.epoch.to_be_bytes()),
+        &hashv(&[&validators]).to_bytes(),
+        &hashv(&[&new_set.aggregate_pk]).to_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// `0x19 0x01 || domainSeparator || hashStruct(rotation)`, signed by the current set
+pub fn typed_rotation(
+    program_id: &Pubkey,
+    chain_id: u64,
+    current_epoch: u64,
+    new_set: &ValidatorSetRef,
+) -> Vec<u8> {
+    [
+        &[0x19, 0x01][..],
+        &domain_separator(program_id, chain_id),
+        &rotation_struct_hash(current_epoch, new_set),
+    ]
+    .concat()
+}
+
+/// The EIP-712 hashStruct of the claim of the rewards of the validator at `index` of
+/// the set of `epoch` to `recipient`
+pub fn claim_struct_hash(epoch: u64, index: u16, recipient: &Pubkey) -> [u8; 32] {
+    hashv(&[
+        &hashv(&[CLAIM_TYPE.as_bytes()]).to_bytes(),
+        &word(&epoch.to_be_bytes()),
+        &word(&index.to_be_bytes()),
+        &recipient.to_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// `0x19 0x01 || domainSeparator || hashStruct(claim)`, signed by an eth validator
+pub fn typed_claim(
+    program_id: &Pubkey,
+    chain_id: u64,
+    epoch: u64,
+    index: u16,
+    recipient: &Pubkey,
+) -> Vec<u8> {
+    [
+        &[0x19, 0x01][..],
+        &domain_separator(program_id, chain_id),
+        &claim_struct_hash(epoch, index, recipient),
+    ]
+    .concat()
+}
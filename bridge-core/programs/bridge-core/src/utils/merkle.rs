@@ -0,0 +1,67 @@
+//! Binary keccak Merkle tree of fixed depth `MERKLE_DEPTH`, the leaves are message hashes
+//! (utils::digest::message_hash) in send order and the empty leaves are zero.
+//! `root` and `proof` rebuild the tree off-chain from the SendMessage events of a chain.
+use anchor_lang::solana_program::keccak::hashv;
+
+pub const MERKLE_DEPTH: usize = 32;
+
+/// `keccak(left || right)`
+pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[left, right]).to_bytes()
+}
+
+/// Root of an empty subtree of every height, `zero_hashes()[0]` is the empty leaf
+pub fn zero_hashes() -> [[u8; 32]; MERKLE_DEPTH] {
+    let mut zeros = [[0u8; 32]; MERKLE_DEPTH];
+    for height in 1..MERKLE_DEPTH {
+        zeros[height] = hash_pair(&zeros[height - 1], &zeros[height - 1]);
+    }
+    zeros
+}
+
+/// Root of the tree holding `leaves`
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let zeros = zero_hashes();
+    let mut level = leaves.to_vec();
+    for zero in zeros.iter() {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(zero)))
+            .collect();
+    }
+    let empty_root = hash_pair(&zeros[MERKLE_DEPTH - 1], &zeros[MERKLE_DEPTH - 1]);
+    level.first().copied().unwrap_or(empty_root)
+}
+
+/// Inclusion proof of the leaf at `index`, its sibling at every height from the leaves up
+pub fn proof(leaves: &[[u8; 32]], index: u64) -> Vec<[u8; 32]> {
+    let zeros = zero_hashes();
+    let mut level = leaves.to_vec();
+    let mut index = index as usize;
+    let mut proof = Vec::with_capacity(MERKLE_DEPTH);
+    for zero in zeros.iter() {
+        proof.push(level.get(index ^ 1).copied().unwrap_or(*zero));
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(zero)))
+            .collect();
+        index >>= 1;
+    }
+    proof
+}
+
+/// Check `proof` links `leaf` at `index` to `root`
+pub fn verify_proof(leaf: &[u8; 32], index: u64, proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    if proof.len() != MERKLE_DEPTH || index >> MERKLE_DEPTH != 0 {
+        return false;
+    }
+    let mut node = *leaf;
+    for (height, sibling) in proof.iter().enumerate() {
+        node = if (index >> height) & 1 == 0 {
+            hash_pair(&node, sibling)
+        } else {
+            hash_pair(sibling, &node)
+        };
+    }
+    node == *root
+}
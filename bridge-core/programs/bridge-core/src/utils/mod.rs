@@ -1,3 +1,7 @@
 // pub mod critbit;
-// pub mod sigverify;
-// pub mod node_allocator;
+pub mod digest;
+pub mod merkle;
+pub mod receiver;
+pub mod schnorr;
+pub mod sigverify;
+// pub mod node_allocator;
\ No newline at end of file
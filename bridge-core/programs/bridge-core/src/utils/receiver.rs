@@ -0,0 +1,50 @@
+//! The `receive_message(msg_header, msg_body)` entrypoint bridge-core delivers verified
+//! messages to. The receiving program gets its delivery authority as its first account,
+//! signed by bridge-core, followed by the accounts the relayer passed to `deliver_message`.
+use crate::{
+    error::ErrorCode,
+    state::config::{MsgHeader, DELIVERY_AUTHORITY_SEED},
+};
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+pub const RECEIVE_MESSAGE_IX: &str = "receive_message";
+
+/// Anchor discriminator of `receive_message`, `sha256("global:receive_message")[..8]`
+pub fn receive_message_discriminator() -> [u8; 8] {
+    hash(format!("global:{}", RECEIVE_MESSAGE_IX).as_bytes()).to_bytes()[..8]
+        .try_into()
+        .unwrap()
+}
+
+/// Instruction data of `receive_message`
+pub fn receive_message_data(msg_header: &MsgHeader, msg_body: &Vec<u8>) -> Result<Vec<u8>> {
+    let mut data = receive_message_discriminator().to_vec();
+    msg_header.serialize(&mut data)?;
+    msg_body.serialize(&mut data)?;
+    Ok(data)
+}
+
+/// The bridge-core PDA signing the `receive_message` calls to `receiver_program`
+pub fn delivery_authority(receiver_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DELIVERY_AUTHORITY_SEED.as_bytes(), receiver_program.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Check a `receive_message` call to `program_id`, the receiving program's own id,
+/// really came from bridge-core and carries a message addressed to it. The authority
+/// is per receiver, so a program it was delivered to can not forward it to another.
+pub fn check_delivery(
+    program_id: &Pubkey,
+    delivery_authority: &AccountInfo,
+    msg_header: &MsgHeader,
+) -> Result<()> {
+    require!(
+        delivery_authority.is_signer
+            && delivery_authority.key() == self::delivery_authority(program_id).0
+            && msg_header.to_addr == program_id.to_bytes(),
+        ErrorCode::DeliveryNotAuthorized
+    );
+    Ok(())
+}
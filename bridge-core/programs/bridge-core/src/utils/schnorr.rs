@@ -0,0 +1,149 @@
+use crate::error::ErrorCode;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{keccak::hashv, secp256k1_recover::secp256k1_recover},
+};
+
+/// 256-bit integer as little endian u64 limbs
+pub type U256 = [u64; 4];
+
+/// Order of the secp256k1 group
+pub const N: U256 = [
+    0xBFD2_5E8C_D036_4141,
+    0xBAAE_DCE6_AF48_A03B,
+    0xFFFF_FFFF_FFFF_FFFE,
+    0xFFFF_FFFF_FFFF_FFFF,
+];
+
+/// Parse a big endian 256-bit integer
+pub fn from_be_bytes(bytes: &[u8; 32]) -> U256 {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 32 - (i + 1) * 8;
+        *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+    }
+    limbs
+}
+
+/// Serialize a 256-bit integer as big endian
+pub fn to_be_bytes(limbs: &U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        let start = 32 - (i + 1) * 8;
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+fn is_zero(a: &U256) -> bool {
+    a.iter().all(|limb| *limb == 0)
+}
+
+fn lt(a: &U256, b: &U256) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+fn wrapping_add(a: &U256, b: &U256) -> (U256, bool) {
+    let mut sum = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        sum[i] = s;
+        carry = c1 || c2;
+    }
+    (sum, carry)
+}
+
+fn wrapping_sub(a: &U256, b: &U256) -> U256 {
+    let mut diff = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        diff[i] = d;
+        borrow = b1 || b2;
+    }
+    diff
+}
+
+/// (a + b) mod N, for a, b < N
+fn add_mod(a: &U256, b: &U256) -> U256 {
+    let (sum, carry) = wrapping_add(a, b);
+    if carry || !lt(&sum, &N) {
+        wrapping_sub(&sum, &N)
+    } else {
+        sum
+    }
+}
+
+/// (a * b) mod N by double-and-add, for a, b < N
+pub fn mul_mod(a: &U256, b: &U256) -> U256 {
+    let mut product = [0u64; 4];
+    for i in (0..256).rev() {
+        product = add_mod(&product, &product);
+        if (a[i / 64] >> (i % 64)) & 1 == 1 {
+            product = add_mod(&product, b);
+        }
+    }
+    product
+}
+
+/// -a mod N, for a < N
+pub fn neg_mod(a: &U256) -> U256 {
+    if is_zero(a) {
+        *a
+    } else {
+        wrapping_sub(&N, a)
+    }
+}
+
+/// Check a compressed secp256k1 public key can be used as an aggregate key
+pub fn check_aggregate_key(aggregate_pk: &[u8; 33]) -> Result<()> {
+    let px = from_be_bytes(aggregate_pk[1..].try_into().unwrap());
+    require!(
+        (aggregate_pk[0] == 2 || aggregate_pk[0] == 3) && !is_zero(&px) && lt(&px, &N),
+        ErrorCode::InvalidAggregateKey
+    );
+    Ok(())
+}
+
+/// Verify a Schnorr signature `e || s` of the aggregate key over `msg_hash`,
+/// where `e = keccak(address(R) || parity || px || msg_hash)` and `s = k + e * x`.
+///
+/// R = s * G - e * P is rebuilt with a single secp256k1_recover call: recovering
+/// with r = px, recovery id = parity, hash = -s * px and s = -e * px yields
+/// px^-1 * (-e * px * P + s * px * G) = R.
+pub fn verify_schnorr(aggregate_pk: &[u8; 33], msg_hash: &[u8; 32], signature: &[u8; 64]) -> Result<()> {
+    check_aggregate_key(aggregate_pk)?;
+    let parity = aggregate_pk[0] - 2;
+    let px_bytes: [u8; 32] = aggregate_pk[1..].try_into().unwrap();
+    let e_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+    let s_bytes: [u8; 32] = signature[32..].try_into().unwrap();
+
+    let px = from_be_bytes(&px_bytes);
+    let e = from_be_bytes(&e_bytes);
+    let s = from_be_bytes(&s_bytes);
+    require!(
+        lt(&e, &N) && lt(&s, &N) && !is_zero(&e),
+        ErrorCode::SignatureVerificationFailed
+    );
+
+    let sp = to_be_bytes(&neg_mod(&mul_mod(&s, &px)));
+    let ep = to_be_bytes(&neg_mod(&mul_mod(&e, &px)));
+    let recovered = secp256k1_recover(&sp, parity, &[px_bytes, ep].concat())
+        .map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+    let nonce_address = &hashv(&[&recovered.0]).to_bytes()[12..];
+
+    let challenge = hashv(&[nonce_address, &[parity], &px_bytes, msg_hash]).to_bytes();
+    require!(
+        challenge == e_bytes,
+        ErrorCode::SignatureVerificationFailed
+    );
+    Ok(())
+}
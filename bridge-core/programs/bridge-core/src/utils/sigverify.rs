@@ -3,6 +3,7 @@ use anchor_lang::{prelude::*, solana_program};
 use solana_program::ed25519_program::ID as ED25519_ID;
 use solana_program::secp256k1_program::ID as SECP256K1_ID;
 use solana_program::instruction::Instruction;
+use solana_program::sysvar::instructions::load_instruction_at_checked;
 use std::convert::TryInto;
 
  /// Verify Ed25519Program instruction fields
@@ -149,4 +150,141 @@ pub fn check_secp256k1_data(data: &[u8], eth_address: &[u8], msg: &[u8], sig: &[
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Collect the eth addresses that signed `msg` through the Secp256k1Program
+/// instructions of the current transaction
+pub fn secp256k1_signers(instructions_sysvar: &AccountInfo, msg: &[u8]) -> Result<Vec<[u8; 20]>> {
+    let mut signers = Vec::new();
+    let mut index: usize = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        if ix.program_id == SECP256K1_ID {
+            let ix_index: u8 = index.try_into().map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+            signers.extend(secp256k1_ix_signers(&ix.data, ix_index, msg)?);
+        }
+        index += 1;
+    }
+    Ok(signers)
+}
+
+/// Parse every signature of a serialized Secp256k1Program instruction, each one
+/// must read its data from the instruction itself and sign exactly `msg`
+pub fn secp256k1_ix_signers(data: &[u8], ix_index: u8, msg: &[u8]) -> Result<Vec<[u8; 20]>> {
+    // According to this layout used by the Secp256k1Program
+    // https://github.com/solana-labs/solana-web3.js/blob/master/src/secp256k1-program.ts#L49
+
+    const SIGNATURE_OFFSETS_SERIALIZED_SIZE:    usize = 11;
+    const ETH_ADDRESS_SIZE:                     usize = 20;
+
+    let num_signatures = *data.first().ok_or(ErrorCode::SignatureVerificationFailed)? as usize;
+    let mut signers = Vec::with_capacity(num_signatures);
+
+    for i in 0..num_signatures {
+        let start = 1 + i * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets = data
+            .get(start..start + SIGNATURE_OFFSETS_SERIALIZED_SIZE)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?;
+
+        let signature_instruction_index     = offsets[2];                                           // Byte  2
+        let eth_address_offset              = u16::from_le_bytes([offsets[3], offsets[4]]) as usize; // Bytes 3,4
+        let eth_address_instruction_index   = offsets[5];                                           // Byte  5
+        let message_data_offset             = u16::from_le_bytes([offsets[6], offsets[7]]) as usize; // Bytes 6,7
+        let message_data_size               = u16::from_le_bytes([offsets[8], offsets[9]]) as usize; // Bytes 8,9
+        let message_instruction_index       = offsets[10];                                          // Byte  10
+
+        // The signature, address and message must all live in this instruction
+        if  signature_instruction_index     != ix_index     ||
+            eth_address_instruction_index   != ix_index     ||
+            message_instruction_index       != ix_index
+        {
+            return Err(ErrorCode::SignatureVerificationFailed.into());
+        }
+
+        let data_msg = data
+            .get(message_data_offset..message_data_offset + message_data_size)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?;
+        if data_msg != msg {
+            return Err(ErrorCode::SignatureVerificationFailed.into());
+        }
+
+        let eth_address: [u8; 20] = data
+            .get(eth_address_offset..eth_address_offset + ETH_ADDRESS_SIZE)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?
+            .try_into()
+            .map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+        signers.push(eth_address);
+    }
+
+    Ok(signers)
+}
+
+/// Collect the ed25519 public keys that signed `msg` through the Ed25519Program
+/// instructions of the current transaction
+pub fn ed25519_signers(instructions_sysvar: &AccountInfo, msg: &[u8]) -> Result<Vec<[u8; 32]>> {
+    let mut signers = Vec::new();
+    let mut index: usize = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        if ix.program_id == ED25519_ID {
+            let ix_index: u16 = index.try_into().map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+            signers.extend(ed25519_ix_signers(&ix.data, ix_index, msg)?);
+        }
+        index += 1;
+    }
+    Ok(signers)
+}
+
+/// Parse every signature of a serialized Ed25519Program instruction, each one
+/// must read its data from the instruction itself and sign exactly `msg`
+pub fn ed25519_ix_signers(data: &[u8], ix_index: u16, msg: &[u8]) -> Result<Vec<[u8; 32]>> {
+    // According to this layout used by the Ed25519Program
+    // https://github.com/solana-labs/solana-web3.js/blob/master/src/ed25519-program.ts#L33
+
+    const SIGNATURE_OFFSETS_START:              usize = 2;
+    const SIGNATURE_OFFSETS_SERIALIZED_SIZE:    usize = 14;
+    const PUBKEY_SIZE:                          usize = 32;
+
+    // u16::MAX refers to the instruction being verified
+    let is_current = |index: u16| index == u16::MAX || index == ix_index;
+
+    let num_signatures = *data.first().ok_or(ErrorCode::SignatureVerificationFailed)? as usize;
+    let mut signers = Vec::with_capacity(num_signatures);
+
+    for i in 0..num_signatures {
+        let start = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets = data
+            .get(start..start + SIGNATURE_OFFSETS_SERIALIZED_SIZE)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?;
+        let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        let signature_instruction_index     = read_u16(2);              // Bytes 2,3
+        let public_key_offset               = read_u16(4) as usize;     // Bytes 4,5
+        let public_key_instruction_index    = read_u16(6);              // Bytes 6,7
+        let message_data_offset             = read_u16(8) as usize;     // Bytes 8,9
+        let message_data_size               = read_u16(10) as usize;    // Bytes 10,11
+        let message_instruction_index       = read_u16(12);             // Bytes 12,13
+
+        // The signature, public key and message must all live in this instruction
+        if  !is_current(signature_instruction_index)    ||
+            !is_current(public_key_instruction_index)   ||
+            !is_current(message_instruction_index)
+        {
+            return Err(ErrorCode::SignatureVerificationFailed.into());
+        }
+
+        let data_msg = data
+            .get(message_data_offset..message_data_offset + message_data_size)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?;
+        if data_msg != msg {
+            return Err(ErrorCode::SignatureVerificationFailed.into());
+        }
+
+        let pubkey: [u8; 32] = data
+            .get(public_key_offset..public_key_offset + PUBKEY_SIZE)
+            .ok_or(ErrorCode::SignatureVerificationFailed)?
+            .try_into()
+            .map_err(|_| ErrorCode::SignatureVerificationFailed)?;
+        signers.push(pubkey);
+    }
+
+    Ok(signers)
+}
@@ -0,0 +1,78 @@
+//! utils::digest pinned to digests computed off-chain by a generic EIP-712 encoder,
+//! so any change to the typed data the validators sign shows up here.
+use anchor_lang::prelude::Pubkey;
+use bridge_core::{
+    state::config::{Chain, MsgHeader},
+    utils::digest::{
+        domain_separator, fraud_struct_hash, message_digest, message_struct_hash, typed_message,
+    },
+    ID,
+};
+
+const CHAIN_ID: u64 = 103;
+const EPOCH: u64 = 7;
+const BODY: &[u8] = b"bridge-core digest pin";
+
+const DOMAIN_SEPARATOR: &str = "87ae14c922225bb835b43edb2bd4af9dd8869031da74dc20dfb133aa29861a69";
+const STRUCT_HASH: &str = "938e0f7b88ef6703658cc3b845471ccbb4df89bce0290c84f6b8e41debb77b8e";
+const DIGEST: &str = "00ead78c3da300559bff1d168f4c21069acd3773853c3b18ff33a3d37f69c6ad";
+const FRAUD_STRUCT_HASH: &str = "8e66d7bfc5c31d91047b9c149cc144930b2d9b2355244396016e6f8b22c266df";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn header() -> MsgHeader {
+    MsgHeader {
+        mtype: 1,
+        nonce: 42,
+        from_chain: Chain {
+            chain_type: 0,
+            chain_id: 1,
+        },
+        from_addr: [0x11; 32],
+        to_chain: Chain {
+            chain_type: 2,
+            chain_id: CHAIN_ID,
+        },
+        to_addr: [0x22; 32],
+        upload_gas_fee: 1_000_000_000,
+    }
+}
+
+#[test]
+fn message_digest_matches_eip712() {
+    let header = header();
+    assert_eq!(hex(&domain_separator(&ID, CHAIN_ID)), DOMAIN_SEPARATOR);
+    assert_eq!(hex(&message_struct_hash(&header, BODY, EPOCH)), STRUCT_HASH);
+
+    let typed = typed_message(&ID, CHAIN_ID, &header, BODY, EPOCH);
+    assert_eq!(hex(&typed), format!("1901{}{}", DOMAIN_SEPARATOR, STRUCT_HASH));
+    assert_eq!(hex(&message_digest(&ID, CHAIN_ID, &header, BODY, EPOCH)), DIGEST);
+}
+
+#[test]
+fn message_digest_binds_epoch_and_chain() {
+    let header = header();
+    assert_ne!(hex(&message_digest(&ID, CHAIN_ID, &header, BODY, EPOCH + 1)), DIGEST);
+    assert_ne!(hex(&message_digest(&ID, CHAIN_ID + 1, &header, BODY, EPOCH)), DIGEST);
+}
+
+#[test]
+fn fraud_struct_hash_binds_challenger() {
+    let from_chain = Chain {
+        chain_type: 0,
+        chain_id: 1,
+    };
+    let root = [0x33; 32];
+    let challenger = Pubkey::new_from_array([0x44; 32]);
+    assert_eq!(
+        hex(&fraud_struct_hash(&from_chain, &root, 5, EPOCH, &challenger)),
+        FRAUD_STRUCT_HASH
+    );
+    let other = Pubkey::new_from_array([0x55; 32]);
+    assert_ne!(
+        hex(&fraud_struct_hash(&from_chain, &root, 5, EPOCH, &other)),
+        FRAUD_STRUCT_HASH
+    );
+}
@@ -0,0 +1,198 @@
+//! Property tests of FromChainNonceWindow against a set based reference model.
+use std::collections::{BTreeMap, BTreeSet};
+
+use bridge_core::state::config::{
+    Chain, DeliveryMode, FromChainNonce, FromChainNonceWindow, NONCE_WINDOW,
+};
+
+/// The documented window semantics, on a plain set of delivered nonces
+struct Model {
+    delivered: BTreeSet<u64>,
+    base_nonce: u64,
+    legacy_ceiling: u64,
+}
+
+impl Model {
+    fn record(&mut self, nonce: u64) -> bool {
+        if nonce < self.base_nonce {
+            return nonce > self.legacy_ceiling;
+        }
+        if nonce >= self.base_nonce + NONCE_WINDOW {
+            self.base_nonce = nonce - (NONCE_WINDOW - 1);
+            let base_nonce = self.base_nonce;
+            self.delivered.retain(|delivered| *delivered >= base_nonce);
+        }
+        self.delivered.insert(nonce)
+    }
+}
+
+/// xorshift64, deterministic so failures reproduce
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Mostly nonces around the latest one, sometimes far behind or ahead of the window
+    fn nonce(&mut self, last_nonce: u64) -> u64 {
+        match self.next() % 10 {
+            0 => last_nonce + self.next() % (4 * NONCE_WINDOW),
+            1 => last_nonce.saturating_sub(self.next() % (4 * NONCE_WINDOW)),
+            _ => (last_nonce + 16).saturating_sub(self.next() % 64),
+        }
+    }
+}
+
+fn empty_window() -> FromChainNonceWindow {
+    FromChainNonceWindow {
+        chain_id: 1,
+        last_nonce: 0,
+        base_nonce: 0,
+        legacy_ceiling: 0,
+        bitmap: [0; 16],
+        from_addr: [1; 32],
+        to_addr: [2; 32],
+        chain_type: 0,
+        delivery_mode: DeliveryMode::Unordered as u8,
+        padding: [0; 6],
+    }
+}
+
+fn assert_same_behavior(window: &mut FromChainNonceWindow, model: &mut Model, rng: &mut Rng) {
+    for _ in 0..5_000 {
+        let nonce = rng.nonce(window.last_nonce);
+        let accepted = window.record(nonce).is_ok();
+        assert_eq!(accepted, model.record(nonce), "nonce {}", nonce);
+        assert_eq!(window.base_nonce, model.base_nonce);
+        for offset in 0..NONCE_WINDOW {
+            let nonce = window.base_nonce + offset;
+            assert_eq!(window.is_delivered(nonce), model.delivered.contains(&nonce));
+        }
+    }
+}
+
+#[test]
+fn window_matches_model() {
+    for seed in 1..=4 {
+        let mut rng = Rng(seed);
+        let mut window = empty_window();
+        let mut model = Model {
+            delivered: BTreeSet::new(),
+            base_nonce: 0,
+            legacy_ceiling: 0,
+        };
+        assert_same_behavior(&mut window, &mut model, &mut rng);
+    }
+}
+
+#[test]
+fn migrated_window_matches_model() {
+    for seed in 1..=4 {
+        let mut rng = Rng(seed);
+        let consumed: BTreeMap<u64, ()> = (0..50)
+            .map(|_| (100 + rng.next() % 500, ()))
+            .collect();
+        let last_nonce = *consumed.keys().last().unwrap();
+        let legacy = FromChainNonce {
+            chain: Chain {
+                chain_type: 0,
+                chain_id: 1,
+            },
+            last_nonce,
+            missing_nonces: bincode::serialize(&consumed).unwrap(),
+        };
+
+        let mut window = FromChainNonceWindow::from_legacy(&legacy, [1; 32], [2; 32]).unwrap();
+        assert_eq!(window.legacy_ceiling, last_nonce);
+        // the legacy rules: consumed nonces and nonces below the window are rejected
+        for nonce in 0..=last_nonce {
+            let legacy_accepts = nonce >= *consumed.keys().next().unwrap()
+                && !consumed.contains_key(&nonce);
+            assert_eq!(window.clone().record(nonce).is_ok(), legacy_accepts);
+        }
+
+        let mut model = Model {
+            delivered: consumed.keys().copied().collect(),
+            base_nonce: window.base_nonce,
+            legacy_ceiling: window.legacy_ceiling,
+        };
+        assert_same_behavior(&mut window, &mut model, &mut rng);
+    }
+}
+
+fn legacy(rng: &mut Rng, low: u64) -> (FromChainNonce, BTreeSet<u64>) {
+    let consumed: BTreeMap<u64, ()> = (0..50)
+        .map(|_| (low + rng.next() % 500, ()))
+        .collect();
+    let legacy = FromChainNonce {
+        chain: Chain {
+            chain_type: 0,
+            chain_id: 1,
+        },
+        last_nonce: *consumed.keys().last().unwrap(),
+        missing_nonces: bincode::serialize(&consumed).unwrap(),
+    };
+    (legacy, consumed.keys().copied().collect())
+}
+
+fn legacy_accepts(consumed: &BTreeSet<u64>, nonce: u64) -> bool {
+    nonce >= *consumed.iter().next().unwrap() && !consumed.contains(&nonce)
+}
+
+#[test]
+fn merged_legacy_windows_reject_what_any_rejects() {
+    for seed in 1..=4 {
+        let mut rng = Rng(seed);
+        let (first, first_consumed) = legacy(&mut rng, 100);
+        let (second, second_consumed) = legacy(&mut rng, 300);
+
+        let mut window = empty_window();
+        window.merge_legacy(&first).unwrap();
+        window.merge_legacy(&second).unwrap();
+        let mut reversed = empty_window();
+        reversed.merge_legacy(&second).unwrap();
+        reversed.merge_legacy(&first).unwrap();
+        assert_eq!(window.base_nonce, reversed.base_nonce);
+        assert_eq!(window.bitmap, reversed.bitmap);
+
+        assert_eq!(window.legacy_ceiling, first.last_nonce.max(second.last_nonce));
+        assert_eq!(window.last_nonce, window.legacy_ceiling);
+        for nonce in 0..=window.legacy_ceiling {
+            let expected =
+                legacy_accepts(&first_consumed, nonce) && legacy_accepts(&second_consumed, nonce);
+            assert_eq!(window.clone().record(nonce).is_ok(), expected, "nonce {}", nonce);
+        }
+    }
+}
+
+#[test]
+fn merge_keeps_channel_deliveries() {
+    let mut rng = Rng(1);
+    let (legacy, _) = legacy(&mut rng, 100);
+    let mut window = empty_window();
+    for nonce in [700, 705, 900] {
+        window.record(nonce).unwrap();
+    }
+    window.merge_legacy(&legacy).unwrap();
+    for nonce in [700, 705, 900] {
+        assert!(window.clone().record(nonce).is_err(), "nonce {}", nonce);
+    }
+    assert!(window.clone().record(legacy.last_nonce).is_err());
+    assert!(window.record(901).is_ok());
+}
+
+#[test]
+fn ordered_window_only_accepts_next_nonce() {
+    let mut rng = Rng(1);
+    let mut window = empty_window();
+    window.delivery_mode = DeliveryMode::Ordered as u8;
+    for _ in 0..5_000 {
+        let nonce = rng.nonce(window.last_nonce);
+        let expected = nonce == window.last_nonce + 1;
+        assert_eq!(window.record(nonce).is_ok(), expected, "nonce {}", nonce);
+    }
+}
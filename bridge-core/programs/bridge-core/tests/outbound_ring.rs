@@ -0,0 +1,53 @@
+//! OutboundRing keeps the commitment of the last OUTBOUND_RING_LEN nonces of a channel.
+use bridge_core::state::config::{OutboundCommitment, OutboundRing, OUTBOUND_RING_LEN};
+
+fn empty_ring() -> OutboundRing {
+    OutboundRing {
+        chain_id: 1,
+        from_addr: [1; 32],
+        to_addr: [2; 32],
+        chain_type: 0,
+        padding: [0; 7],
+        entries: [OutboundCommitment {
+            message_hash: [0; 32],
+            nonce: 0,
+            slot: 0,
+        }; OUTBOUND_RING_LEN],
+    }
+}
+
+fn message_hash(nonce: u64) -> [u8; 32] {
+    let mut message_hash = [0xcd; 32];
+    message_hash[..8].copy_from_slice(&nonce.to_be_bytes());
+    message_hash
+}
+
+#[test]
+fn keeps_the_last_nonces() {
+    let len = OUTBOUND_RING_LEN as u64;
+    let mut ring = empty_ring();
+    // nonces start at 1, nonce 0 is never sent
+    assert!(ring.get(0).is_none());
+    for nonce in 1..=3 * len + 5 {
+        ring.record(nonce, message_hash(nonce), 100 + nonce);
+
+        let entry = ring.get(nonce).unwrap();
+        assert_eq!(entry.message_hash, message_hash(nonce));
+        assert_eq!(entry.slot, 100 + nonce);
+        for kept in nonce.saturating_sub(len - 1).max(1)..=nonce {
+            assert_eq!(ring.get(kept).unwrap().nonce, kept);
+        }
+        // the nonce sharing its entry was overwritten
+        if nonce > len {
+            assert!(ring.get(nonce - len).is_none());
+        }
+        assert!(ring.get(nonce + 1).is_none());
+        assert!(ring.get(nonce + len).is_none());
+    }
+    assert!(ring.get(0).is_none());
+}
+
+#[test]
+fn layout_matches_len() {
+    assert_eq!(std::mem::size_of::<OutboundRing>(), OutboundRing::LEN);
+}
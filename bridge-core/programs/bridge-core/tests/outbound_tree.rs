@@ -0,0 +1,47 @@
+//! The incremental OutboundTree against the off-chain tree of utils::merkle.
+use bridge_core::{
+    state::config::OutboundTree,
+    utils::merkle::{proof, root, verify_proof, MERKLE_DEPTH},
+};
+
+fn empty_tree() -> OutboundTree {
+    OutboundTree {
+        chain_id: 1,
+        count: 0,
+        root: [0; 32],
+        branch: [[0; 32]; MERKLE_DEPTH],
+        chain_type: 0,
+        padding: [0; 7],
+    }
+}
+
+fn leaf(index: u64) -> [u8; 32] {
+    let mut leaf = [0xab; 32];
+    leaf[..8].copy_from_slice(&index.to_be_bytes());
+    leaf
+}
+
+#[test]
+fn incremental_root_matches_full_tree() {
+    let mut tree = empty_tree();
+    let mut leaves = Vec::new();
+    for index in 0..70 {
+        assert_eq!(tree.append(leaf(index)).unwrap(), index);
+        leaves.push(leaf(index));
+        assert_eq!(tree.root, root(&leaves), "{} leaves", leaves.len());
+    }
+}
+
+#[test]
+fn proofs_verify_against_root() {
+    let leaves: Vec<[u8; 32]> = (0..37).map(leaf).collect();
+    let root = root(&leaves);
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = proof(&leaves, index as u64);
+        assert!(verify_proof(leaf, index as u64, &proof, &root));
+        // bound to the position and to the leaf
+        assert!(!verify_proof(leaf, index as u64 + 1, &proof, &root));
+        assert!(!verify_proof(&[0; 32], index as u64, &proof, &root));
+        assert!(!verify_proof(leaf, index as u64, &proof[1..], &root));
+    }
+}
@@ -0,0 +1,67 @@
+//! utils::receiver::check_delivery, the check a receiving program runs on receive_message.
+use anchor_lang::prelude::*;
+use bridge_core::{
+    state::config::{Chain, MsgHeader},
+    utils::receiver::{check_delivery, delivery_authority},
+};
+
+fn header(to_addr: &Pubkey) -> MsgHeader {
+    MsgHeader {
+        mtype: 0,
+        nonce: 1,
+        from_chain: Chain {
+            chain_type: 0,
+            chain_id: 1,
+        },
+        from_addr: [1; 32],
+        to_chain: Chain {
+            chain_type: 2,
+            chain_id: 103,
+        },
+        to_addr: to_addr.to_bytes(),
+        upload_gas_fee: 0,
+    }
+}
+
+/// Whether check_delivery accepts `authority` as a signer in `program_id`
+fn accepts(program_id: &Pubkey, authority: &Pubkey, to_addr: &Pubkey, is_signer: bool) -> bool {
+    let mut lamports = 0;
+    let mut data = [];
+    let info = AccountInfo::new(
+        authority,
+        is_signer,
+        false,
+        &mut lamports,
+        &mut data,
+        &bridge_core::ID,
+        false,
+        0,
+    );
+    check_delivery(program_id, &info, &header(to_addr)).is_ok()
+}
+
+#[test]
+fn accepts_own_authority() {
+    let receiver = Pubkey::new_unique();
+    let (authority, _) = delivery_authority(&receiver);
+    assert!(accepts(&receiver, &authority, &receiver, true));
+    assert!(!accepts(&receiver, &authority, &receiver, false));
+}
+
+#[test]
+fn rejects_forwarded_authority() {
+    // a program that got a delivery forwards the signed authority to another receiver
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+    let (forwarded, _) = delivery_authority(&first);
+    assert_ne!(forwarded, delivery_authority(&second).0);
+    assert!(!accepts(&second, &forwarded, &second, true));
+    assert!(!accepts(&second, &forwarded, &first, true));
+}
+
+#[test]
+fn rejects_message_for_another_program() {
+    let receiver = Pubkey::new_unique();
+    let (authority, _) = delivery_authority(&receiver);
+    assert!(!accepts(&receiver, &authority, &Pubkey::new_unique(), true));
+}
@@ -0,0 +1,102 @@
+//! utils::schnorr against vectors computed off-chain with plain secp256k1 arithmetic.
+use anchor_lang::error::Error;
+use bridge_core::{
+    error::ErrorCode,
+    utils::schnorr::{from_be_bytes, mul_mod, neg_mod, to_be_bytes, verify_schnorr, N},
+};
+
+const AGGREGATE_PK: &str = "0278ca99600106d418a78a7119ff4b72101f9a0bafbf628f81179943c5ec70b014";
+const MSG_HASH: &str = "9f92936bf6e4d64697cb4fde274bbe0f26ded786ee929d10f53ccb303b89628c";
+const E: &str = "61f742c2e94918b7094995f27929f68cc8bd15f63742ce0d6223d7196ace2d61";
+const S: &str = "2ac52ad657f2c49c7856a599755933babbb35ad162bc405967fb69465202950d";
+
+fn hex<const LEN: usize>(hex: &str) -> [u8; LEN] {
+    let mut bytes = [0u8; LEN];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    bytes
+}
+
+fn signature(e: [u8; 32], s: [u8; 32]) -> [u8; 64] {
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&e);
+    signature[32..].copy_from_slice(&s);
+    signature
+}
+
+fn verification_failed() -> Error {
+    ErrorCode::SignatureVerificationFailed.into()
+}
+
+#[test]
+fn accepts_known_signature() {
+    verify_schnorr(&hex(AGGREGATE_PK), &hex(MSG_HASH), &signature(hex(E), hex(S))).unwrap();
+}
+
+#[test]
+fn rejects_wrong_challenge() {
+    let mut e = hex::<32>(E);
+    e[31] ^= 1;
+    let result = verify_schnorr(&hex(AGGREGATE_PK), &hex(MSG_HASH), &signature(e, hex(S)));
+    assert_eq!(result.unwrap_err(), verification_failed());
+}
+
+#[test]
+fn rejects_wrong_response() {
+    let mut s = hex::<32>(S);
+    s[31] ^= 1;
+    let result = verify_schnorr(&hex(AGGREGATE_PK), &hex(MSG_HASH), &signature(hex(E), s));
+    assert_eq!(result.unwrap_err(), verification_failed());
+}
+
+#[test]
+fn rejects_tampered_message() {
+    let mut msg_hash = hex::<32>(MSG_HASH);
+    msg_hash[0] ^= 1;
+    let result = verify_schnorr(&hex(AGGREGATE_PK), &msg_hash, &signature(hex(E), hex(S)));
+    assert_eq!(result.unwrap_err(), verification_failed());
+}
+
+#[test]
+fn rejects_scalars_out_of_range() {
+    let n = to_be_bytes(&N);
+    for signature in [signature(n, hex(S)), signature(hex(E), n), signature([0; 32], hex(S))] {
+        let result = verify_schnorr(&hex(AGGREGATE_PK), &hex(MSG_HASH), &signature);
+        assert_eq!(result.unwrap_err(), verification_failed());
+    }
+}
+
+#[test]
+fn rejects_aggregate_key_at_or_above_order() {
+    let mut aggregate_pk = [2u8; 33];
+    aggregate_pk[1..].copy_from_slice(&to_be_bytes(&N));
+    let result = verify_schnorr(&aggregate_pk, &hex(MSG_HASH), &signature(hex(E), hex(S)));
+    assert_eq!(result.unwrap_err(), ErrorCode::InvalidAggregateKey.into());
+
+    aggregate_pk[1..].copy_from_slice(&[0xff; 32]);
+    let result = verify_schnorr(&aggregate_pk, &hex(MSG_HASH), &signature(hex(E), hex(S)));
+    assert_eq!(result.unwrap_err(), ErrorCode::InvalidAggregateKey.into());
+}
+
+#[test]
+fn modular_arithmetic() {
+    let a = from_be_bytes(&hex("3ac225168df54212a25c1c01fd35bebfea408fdac2e31ddd6f80a4bbf9a5f1cb"));
+    let b = from_be_bytes(&hex("b5553de315e0edf504d9150af82dafa5c4667fa618ed0a6f19c69b41166c5510"));
+    assert_eq!(
+        to_be_bytes(&mul_mod(&a, &b)),
+        hex("9ae63df37904da7896f74aba27fb3b14a3a986315042fba0234c70aea73351f1")
+    );
+    assert_eq!(
+        to_be_bytes(&neg_mod(&a)),
+        hex("c53ddae9720abded5da3e3fe02ca413ed06e4d0bec65825e5051b9d0d6904f76")
+    );
+
+    let one = [1, 0, 0, 0];
+    let minus_one = neg_mod(&one);
+    assert_eq!(mul_mod(&minus_one, &minus_one), one);
+    assert_eq!(mul_mod(&a, &one), a);
+    assert_eq!(mul_mod(&a, &[0; 4]), [0; 4]);
+    assert_eq!(neg_mod(&[0; 4]), [0; 4]);
+    assert_eq!(neg_mod(&neg_mod(&a)), a);
+}
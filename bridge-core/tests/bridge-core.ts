@@ -4,6 +4,7 @@ import { BridgeCore } from "../target/types/bridge_core";
 import { publicKey } from "@coral-xyz/anchor/dist/cjs/utils";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { bs58 } from "@coral-xyz/anchor/dist/cjs/utils/bytes";
+import { expect } from "chai";
 
 describe("bridge-core", () => {
   // Configure the client to use the local cluster.
@@ -45,14 +46,14 @@ describe("bridge-core", () => {
     // console.log("Multisig info initialized:", multisigInfo);
   });
 
-  it("Change admin", async () => {
+  it("Propose admin", async () => {
     return;
     // [bridgeConfig, bump] = await anchor.web3.PublicKey.findProgramAddressSync(
     //   [Buffer.from("global")],
     //   program.programId
     // );
 
-    // let instruction = await program.methods.changeAdmin(new anchor.web3.PublicKey(""))
+    // let instruction = await program.methods.proposeAdmin(new anchor.web3.PublicKey(""))
     //   .accounts({
     //     admin: provider.wallet.publicKey,
     //     bridgeConfig: bridgeConfig,
@@ -75,6 +76,27 @@ describe("bridge-core", () => {
     console.log("Multisig info initialized:", multisigInfo);
   });
 
+  it("Register bridge-token app", async () => {
+    const bridgeToken = new PublicKey("C7JbQuFuapFwBK7oCunFyN8zSi5Hmfgrq1LZzrmiago3");
+    const [appRegistration] = PublicKey.findProgramAddressSync(
+      [bridgeToken.toBuffer(), Buffer.from("app")],
+      program.programId
+    );
+    const evmChain = { chainType: 1, chainId: new anchor.BN(1) };
+
+    let signature = await program.methods.registerApp("bridge-token", [evmChain], Buffer.from([0]), null)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        program: bridgeToken,
+      })
+      .rpc();
+    console.log(signature)
+
+    const app = await program.account.appRegistration.fetch(appRegistration);
+    expect(app.enabled).to.equal(true);
+    expect(app.feeOverride).to.equal(null);
+  });
+
   it("withdrow fee", async () => {
     let [messageFee, bump] = await anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("vaultFee")],
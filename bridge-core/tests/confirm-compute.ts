@@ -0,0 +1,253 @@
+// Compares the compute units of confirm_message and confirm_message_precompiled. The
+// test activates a throwaway validator set, so it only runs against a local validator.
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BridgeCore } from "../target/types/bridge_core";
+import {
+  Keypair,
+  Secp256k1Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
+} from "@solana/web3.js";
+// keccak ships with @solana/web3.js, which hashes its secp256k1 messages with it
+import { keccak_256 } from "@noble/hashes/sha3";
+import { expect } from "chai";
+
+// compute units charged by each secp256k1_recover syscall
+const SECP256K1_RECOVER_COST = 25_000;
+
+describe("confirm compute", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.BridgeCore as Program<BridgeCore>;
+
+  it("Compare confirm compute usage", async function () {
+    const endpoint = provider.connection.rpcEndpoint;
+    if (!endpoint.includes("localhost") && !endpoint.includes("127.0.0.1")) {
+      this.skip();
+    }
+
+    const [bridgeConfig, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("global")],
+      program.programId
+    );
+    await program.methods
+      .initialize(bump)
+      .accountsPartial({ bridgeConfig, authority: provider.wallet.publicKey })
+      .rpc();
+
+    const [validatorSet] = anchor.web3.PublicKey.findProgramAddressSync(
+      [new anchor.BN(1).toArrayLike(Buffer, "be", 8), Buffer.from("validatorSet")],
+      program.programId
+    );
+
+    // three throwaway validators with a quorum of two, the eth address of each key is
+    // read back from a Secp256k1Program instruction signed with it
+    const validatorKeys = [1, 2, 3].map(() => Keypair.generate().secretKey.slice(0, 32));
+    const ethAddresses = validatorKeys.map((privateKey) =>
+      Secp256k1Program.createInstructionWithPrivateKey({
+        privateKey,
+        message: Buffer.alloc(0),
+      }).data.subarray(12, 32)
+    );
+    await program.methods
+      .migrateValidatorSet()
+      .accountsPartial({ admin: provider.wallet.publicKey, bridgeConfig, validatorSet })
+      .rpc();
+    await program.methods
+      .addSigners(ethAddresses.map((address) => ({ eth: { 0: Array.from(address) } })))
+      .accountsPartial({ admin: provider.wallet.publicKey, bridgeConfig, validatorSet })
+      .rpc();
+    await program.methods
+      .setQuorum({ weight: { 0: new anchor.BN(2) } })
+      .accountsPartial({ admin: provider.wallet.publicKey, bridgeConfig, validatorSet })
+      .rpc();
+    await program.methods
+      .initValidatorEpoch()
+      .accountsPartial({ admin: provider.wallet.publicKey, bridgeConfig, validatorSet })
+      .rpc();
+
+    // the caller program is never executed, its auth pda is marked as signer and the
+    // transactions are simulated without signature verification
+    const callerProgram = Keypair.generate().publicKey;
+    const [callerAuthPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bridge")],
+      callerProgram
+    );
+    const fromChain = { chainType: 0, chainId: new anchor.BN(1) };
+    const msgHeader = {
+      mtype: 0,
+      nonce: new anchor.BN(1),
+      fromChain,
+      fromAddr: Array.from(Keypair.generate().publicKey.toBytes()),
+      toChain: { chainType: 2, chainId: new anchor.BN(103) },
+      toAddr: Array.from(callerProgram.toBytes()),
+      uploadGasFee: new anchor.BN(0),
+    };
+    const msgBody = Buffer.from("compute usage");
+    // EIP-712 style typed data of the message, see utils/digest.rs
+    const word = (value: number | anchor.BN) =>
+      new anchor.BN(value).toArrayLike(Buffer, "be", 32);
+    const keccak = (...data: Uint8Array[]) => Buffer.from(keccak_256(Buffer.concat(data)));
+    const domainSeparator = keccak(
+      keccak(
+        Buffer.from(
+          "EIP712Domain(string name,string version,uint256 chainId,bytes32 verifyingContract)"
+        )
+      ),
+      keccak(Buffer.from("bridge-core")),
+      keccak(Buffer.from("1")),
+      word(103),
+      program.programId.toBuffer()
+    );
+    const structHash = keccak(
+      keccak(
+        Buffer.from(
+          "Message(uint8 mtype,uint64 nonce,uint8 fromChainType,uint64 fromChainId,bytes32 fromAddr,uint8 toChainType,uint64 toChainId,bytes32 toAddr,uint128 uploadGasFee,uint64 epoch,bytes body)"
+        )
+      ),
+      word(msgHeader.mtype),
+      word(msgHeader.nonce),
+      word(fromChain.chainType),
+      word(fromChain.chainId),
+      Buffer.from(msgHeader.fromAddr),
+      word(msgHeader.toChain.chainType),
+      word(msgHeader.toChain.chainId),
+      Buffer.from(msgHeader.toAddr),
+      word(msgHeader.uploadGasFee),
+      word(1),
+      keccak(msgBody)
+    );
+    const message = Buffer.concat([Buffer.from([0x19, 0x01]), domainSeparator, structHash]);
+    // a quorum of two: eth address at 12, signature at 32 and recovery id at 96
+    const precompiles = validatorKeys.slice(0, 2).map((privateKey, instructionIndex) =>
+      Secp256k1Program.createInstructionWithPrivateKey({ privateKey, message, instructionIndex })
+    );
+    const signatures = precompiles.map(({ data }) => [
+      ...data.subarray(32, 96),
+      27 + data[96],
+    ]);
+
+    // the receipt is keyed by keccak(header || body), independent of the epoch
+    const headerBytes = Buffer.concat([
+      Buffer.from([msgHeader.mtype]),
+      msgHeader.nonce.toArrayLike(Buffer, "be", 8),
+      Buffer.from([fromChain.chainType]),
+      fromChain.chainId.toArrayLike(Buffer, "be", 8),
+      Buffer.from(msgHeader.fromAddr),
+      Buffer.from([msgHeader.toChain.chainType]),
+      msgHeader.toChain.chainId.toArrayLike(Buffer, "be", 8),
+      Buffer.from(msgHeader.toAddr),
+      msgHeader.uploadGasFee.toArrayLike(Buffer, "be", 16),
+    ]);
+    const [messageReceipt] = anchor.web3.PublicKey.findProgramAddressSync(
+      [keccak(headerBytes, msgBody), Buffer.from("receipt")],
+      program.programId
+    );
+
+    const [validatorRewards] = anchor.web3.PublicKey.findProgramAddressSync(
+      [new anchor.BN(1).toArrayLike(Buffer, "be", 8), Buffer.from("validatorRewards")],
+      program.programId
+    );
+
+    const [fromChainNonceAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from([fromChain.chainType]),
+        fromChain.chainId.toArrayLike(Buffer, "be", 8),
+        Buffer.from("fromNonce"),
+        Buffer.from(msgHeader.fromAddr),
+        callerProgram.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const accounts = {
+      fromChainNonceAccount,
+      bridgeConfig,
+      messageReceipt,
+      validatorSet,
+      validatorRewards,
+      user: provider.wallet.publicKey,
+      receiver: provider.wallet.publicKey,
+      callerAuthPda,
+      callerProgram,
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+    };
+    const withSignerPda = (ix: TransactionInstruction) => {
+      ix.keys.forEach((key) => {
+        if (key.pubkey.equals(callerAuthPda)) key.isSigner = true;
+      });
+      return ix;
+    };
+    const simulate = async (instructions: TransactionInstruction[]) => {
+      const tx = new VersionedTransaction(
+        new TransactionMessage({
+          payerKey: provider.wallet.publicKey,
+          recentBlockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+          instructions,
+        }).compileToV0Message()
+      );
+      const { value } = await provider.connection.simulateTransaction(tx, {
+        sigVerify: false,
+        replaceRecentBlockhash: true,
+      });
+      return value;
+    };
+    const unitsConsumed = async (instructions: TransactionInstruction[]) => {
+      const result = await simulate(instructions);
+      expect(result.err, JSON.stringify(result.logs)).to.be.null;
+      return result.unitsConsumed;
+    };
+    // the receiving app opens its channel before any confirm, in both simulations alike
+    const initChannel = withSignerPda(
+      await program.methods
+        .initChannel(fromChain, msgHeader.fromAddr, { unordered: {} })
+        .accountsPartial({
+          fromChainNonceAccount,
+          payer: provider.wallet.publicKey,
+          callerAuthPda,
+          callerProgram,
+        })
+        .instruction()
+    );
+    const confirmPrecompiled = withSignerPda(
+      await program.methods
+        .confirmMessagePrecompiled(msgHeader, msgBody)
+        .accountsPartial(accounts)
+        .instruction()
+    );
+
+    const recovered = await unitsConsumed([
+      initChannel,
+      withSignerPda(
+        await program.methods
+          .confirmMessage(msgHeader, msgBody, Buffer.alloc(0), signatures)
+          .accountsPartial(accounts)
+          .instruction()
+      ),
+    ]);
+    const precompiled = await unitsConsumed([...precompiles, initChannel, confirmPrecompiled]);
+
+    // bridge-core only reads the eth address and the message of a precompile, a broken
+    // signature can only be rejected by the Secp256k1Program itself
+    const tampered = new TransactionInstruction({
+      keys: [],
+      programId: Secp256k1Program.programId,
+      data: Buffer.from(precompiles[0].data),
+    });
+    tampered.data[32] ^= 0xff;
+    const rejected = await simulate([tampered, precompiles[1], initChannel, confirmPrecompiled]);
+    expect(rejected.err).to.not.be.null;
+    expect(rejected.logs ?? []).to.not.include(`Program ${program.programId} success`);
+
+    console.log("confirm_message units:", recovered);
+    console.log("confirm_message_precompiled units:", precompiled);
+    // the precompiled path skips one secp256k1_recover syscall per signature
+    expect(recovered - precompiled).to.be.greaterThan(
+      signatures.length * SECP256K1_RECOVER_COST * 0.8
+    );
+  });
+});
@@ -22,4 +22,8 @@ pub enum ErrorCode {
     TokenRelationNotFound,
     #[msg("Staked decrease too large")]
     StakedDecreaseTooLarge,
+    #[msg("Pending admin is invalid")]
+    InvalidPendingAdmin,
+    #[msg("No pending admin or signer is not the pending admin")]
+    NoPendingAdmin,
 }
@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
-use bridge_core::state::config::Chain;
+use bridge_core::state::config::{Chain, DeliveryMode, FromChainNonceWindow};
 
-use crate::state::{
-    config::{ChainRelation, ConfigInfo, TokenRelation}, BRIDGE_SEED, CHAIN_RELATION_SEED
+use crate::{
+    error::ErrorCode,
+    event::{AdminAccepted, AdminProposalCancelled, AdminProposed},
+    state::{
+        config::{ChainRelation, ConfigInfo, TokenRelation}, BRIDGE_SEED, CHAIN_RELATION_SEED
+    },
 };
 
 pub fn initialize(ctx: Context<ConfInitialize>, bump: u8) -> Result<()> {
@@ -13,8 +17,47 @@ pub fn initialize(ctx: Context<ConfInitialize>, bump: u8) -> Result<()> {
     Ok(())
 }
 
-pub fn change_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
-    ctx.accounts.bridge_config.admin = new_admin;
+/// Propose a new admin, who must accept the handover before it takes effect
+pub fn propose_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
+    require!(new_admin != Pubkey::default(), ErrorCode::InvalidPendingAdmin);
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    bridge_config.pending_admin = new_admin;
+
+    emit!(AdminProposed {
+        admin: bridge_config.admin,
+        pending_admin: new_admin,
+    });
+    Ok(())
+}
+
+/// Accept the admin handover, signed by the pending admin
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    let old_admin = bridge_config.admin;
+    bridge_config.admin = bridge_config.pending_admin;
+    bridge_config.pending_admin = Pubkey::default();
+
+    emit!(AdminAccepted {
+        old_admin,
+        new_admin: bridge_config.admin,
+    });
+    Ok(())
+}
+
+/// Cancel a pending admin handover
+pub fn cancel_admin_proposal(ctx: Context<BridgeConf>) -> Result<()> {
+    let bridge_config = &mut ctx.accounts.bridge_config;
+    require!(
+        bridge_config.pending_admin != Pubkey::default(),
+        ErrorCode::NoPendingAdmin
+    );
+    let pending_admin = bridge_config.pending_admin;
+    bridge_config.pending_admin = Pubkey::default();
+
+    emit!(AdminProposalCancelled {
+        admin: bridge_config.admin,
+        pending_admin,
+    });
     Ok(())
 }
 
@@ -71,6 +114,28 @@ pub fn chain_relationship(
     Ok(())
 }
 
+/// Open the bridge-core channel from the executor of `from_chain` to this program,
+/// transfers are retryable so the channel accepts nonces in any order
+pub fn init_channel(ctx: Context<InitChannel>, from_chain: Chain) -> Result<()> {
+    let seeds = &[BRIDGE_SEED.as_bytes(), &[ctx.bumps.bridge_authority]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_program = ctx.accounts.bridge_core_program.to_account_info();
+    let cpi_accounts = bridge_core::cpi::accounts::InitChannel {
+        from_chain_nonce_account: ctx.accounts.from_chain_nonce_account.to_account_info(),
+        payer: ctx.accounts.admin.to_account_info(),
+        caller_auth_pda: ctx.accounts.bridge_authority.to_account_info(),
+        caller_program: ctx.accounts.program_id.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    bridge_core::cpi::init_channel(
+        cpi_ctx,
+        from_chain,
+        ctx.accounts.chain_relation.from_excutor,
+        DeliveryMode::Unordered,
+    )
+}
+
 #[derive(Accounts)]
 #[instruction(bump: u8)]
 pub struct ConfInitialize<'info> {
@@ -92,6 +157,14 @@ pub struct BridgeConf<'info> {
     pub bridge_config: Account<'info, ConfigInfo>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(constraint = pending_admin.key() == bridge_config.pending_admin @ ErrorCode::NoPendingAdmin)]
+    pub pending_admin: Signer<'info>,
+    #[account(mut, seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+}
+
 #[derive(Accounts)]
 #[instruction(from_chain: Chain, from_token: [u8; 32])]
 pub struct TokenRelationship<'info> {
@@ -129,3 +202,25 @@ pub struct ChainRelationship<'info> {
     pub chain_relation: Account<'info, ChainRelation>,
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+#[instruction(from_chain: Chain)]
+pub struct InitChannel<'info> {
+    #[account(mut, constraint = admin.key() == bridge_config.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump)]
+    pub bridge_config: Account<'info, ConfigInfo>,
+    #[account(seeds = [&from_chain.combain_chain(), CHAIN_RELATION_SEED.as_bytes()], bump)]
+    pub chain_relation: Account<'info, ChainRelation>,
+    /// CHECK: initialized by bridge-core
+    #[account(mut, seeds = [&from_chain.combain_chain(), FromChainNonceWindow::SEED_SUFFIX.as_bytes(), &chain_relation.from_excutor, crate::ID.as_ref()], bump, seeds::program = bridge_core_program.key())]
+    pub from_chain_nonce_account: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [BRIDGE_SEED.as_bytes()], bump)]
+    pub bridge_authority: AccountInfo<'info>,
+    pub bridge_core_program: Program<'info, bridge_core::program::BridgeCore>,
+    /// CHECK:
+    #[account(address = crate::ID)]
+    pub program_id: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
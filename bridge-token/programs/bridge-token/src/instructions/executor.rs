@@ -1,16 +1,22 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{program::invoke, system_instruction},
+    solana_program::{program::invoke, system_instruction, sysvar::instructions as sysvar_instructions},
 };
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     token::{self, spl_token, Mint, MintTo, Token, TokenAccount},
 };
-use bridge_core::state::config::{Chain, ConfigInfo, MsgHeader, ToChainNonce, MESSAGE_FEE_SEED};
+use bridge_core::{
+    state::config::{
+        AppRegistration, Chain, ChainFee, ChainPause, ConfigInfo, MsgHeader, OutboundRing,
+        OutboundTree, PendingMessage, ToChainNonce, MESSAGE_FEE_SEED,
+    },
+    utils::digest::message_hash,
+};
 
 use crate::state::{
     config::{ChainRelation, TokenRelation},
-    executor::MsgBody,
+    executor::{BridgeQuote, FinishQuote, MsgBody},
     pool::Pool,
     MintType, BRIDGE_SEED, CHAIN_RELATION_SEED,
 };
@@ -94,9 +100,14 @@ pub fn bridge_proposal(
     let cpi_program = ctx.accounts.bridge_core_program.to_account_info();
     let cpi_accounts = bridge_core::cpi::accounts::SendToOtherChain {
         to_chain_nonce_account: ctx.accounts.to_chain_nonce_account.to_account_info(),
+        outbound_ring: ctx.accounts.outbound_ring.to_account_info(),
+        outbound_tree: ctx.accounts.outbound_tree.to_account_info(),
         sender: ctx.accounts.sender.to_account_info(),
         message_fee: ctx.accounts.message_fee.to_account_info(),
         bridge_config: ctx.accounts.bridge_config.to_account_info(),
+        chain_pause: ctx.accounts.chain_pause.to_account_info(),
+        chain_fee: ctx.accounts.chain_fee.to_account_info(),
+        app_registration: ctx.accounts.app_registration.to_account_info(),
         caller_auth_pda: ctx.accounts.bridge_authority.to_account_info(),
         caller_program: ctx.accounts.program_id.to_account_info(),
         system_program: ctx.accounts.system_program.to_account_info(),
@@ -122,6 +133,61 @@ pub fn bridge_finish(
     accum_pk: Vec<u8>,
     signatures: Vec<[u8; 65]>,
 ) -> Result<()> {
+    ctx.accounts.check_message(&msg_header)?;
+
+    // verify msg
+    let seeds = &[BRIDGE_SEED.as_bytes(), &[ctx.bumps.bridge_authority]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_program = ctx.accounts.bridge_core_program.to_account_info();
+    let cpi_accounts = ctx.accounts.confirm_accounts();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    bridge_core::cpi::confirm_message(
+        cpi_ctx,
+        msg_header.clone(),
+        msg_body.to_evm_buffer(),
+        accum_pk,
+        signatures,
+    )?;
+
+    ctx.accounts.release(&msg_header, &msg_body, signer_seeds)
+}
+
+/// bridge_finish for a message proven under a Merkle root posted to bridge-core,
+/// `leaf_index` being its position in the outbound tree of the source chain
+pub fn bridge_finish_with_proof(
+    ctx: Context<ProofConsumption>,
+    msg_header: MsgHeader,
+    msg_body: MsgBody,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let consumption = &mut ctx.accounts.consumption;
+    consumption.check_message(&msg_header)?;
+
+    // verify msg
+    let seeds = &[BRIDGE_SEED.as_bytes(), &[ctx.bumps.consumption.bridge_authority]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_program = consumption.bridge_core_program.to_account_info();
+    let cpi_accounts = bridge_core::cpi::accounts::ConfirmWithProof {
+        confirm: consumption.confirm_accounts(),
+        root_record: ctx.accounts.root_record.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    bridge_core::cpi::confirm_message_with_proof(
+        cpi_ctx,
+        msg_header.clone(),
+        msg_body.to_evm_buffer(),
+        leaf_index,
+        proof,
+    )?;
+
+    consumption.release(&msg_header, &msg_body, signer_seeds)
+}
+
+/// Execute a transfer message already verified into a bridge-core PendingMessage.
+/// Permissionless and retryable: when the pools can not cover the transfer the attempt
+/// is recorded as failed and the message can be executed again once they are refilled.
+pub fn bridge_execute(ctx: Context<Execution>, msg_header: MsgHeader, msg_body: MsgBody) -> Result<()> {
     require!(
         msg_header.from_addr == ctx.accounts.chain_relation.from_excutor,
         crate::error::ErrorCode::SenderAddrNotMatch
@@ -132,147 +198,246 @@ pub fn bridge_finish(
         crate::error::ErrorCode::ExecuteAddrNotMatch
     );
 
-    // verify msg
+    let seeds = &[BRIDGE_SEED.as_bytes(), &[ctx.bumps.bridge_authority]];
+    let signer_seeds = &[&seeds[..]];
     let cpi_program = ctx.accounts.bridge_core_program.to_account_info();
-    let cpi_accounts = bridge_core::cpi::accounts::ConfirmFromOtherChain {
-        from_chain_nonce_account: ctx.accounts.from_chain_nonce_account.to_account_info(),
+    let cpi_accounts = bridge_core::cpi::accounts::ExecuteMessage {
+        pending_message: ctx.accounts.pending_message.to_account_info(),
         bridge_config: ctx.accounts.bridge_config.to_account_info(),
-        user: ctx.accounts.sender.to_account_info(),
-        receiver: ctx.accounts.receiver.to_account_info(),
+        chain_pause: ctx.accounts.chain_pause.to_account_info(),
+        verifier: ctx.accounts.verifier.to_account_info(),
+        executor: ctx.accounts.sender.to_account_info(),
         caller_auth_pda: ctx.accounts.bridge_authority.to_account_info(),
         caller_program: ctx.accounts.program_id.to_account_info(),
-        system_program: ctx.accounts.system_program.to_account_info(),
     };
-
-    let seeds = &[BRIDGE_SEED.as_bytes(), &[ctx.bumps.bridge_authority]];
-    let signer_seeds = &[&seeds[..]];
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-    bridge_core::cpi::confirm_message(
-        cpi_ctx,
-        msg_header.clone(),
-        msg_body.to_evm_buffer(),
-        accum_pk,
-        signatures,
-    )?;
 
-    // decode the body
-    if ctx.accounts.token_relation.mint_type == MintType::Mint as u8 {
-        let from_decimals = ctx.accounts.token_relation.from_decimals;
-        let to_decimals = ctx.accounts.token_relation.to_decimals;
-        let all_amount = msg_body
-            .all_amount
-            .checked_mul(10u128.pow(to_decimals.into()))
-            .and_then(|v| v.checked_div(10u128.pow(from_decimals.into())))
-            .expect("overflow in decimals conversion");
-        msg!("mint token==> {}", all_amount);
-        // mint token
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.token_mint.to_account_info(),
-                to: ctx.accounts.receiver_token_account.to_account_info(),
-                authority: ctx.accounts.bridge_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::mint_to(cpi_ctx, all_amount as u64)?;
+    let mut release = Release {
+        sender: &ctx.accounts.sender,
+        receiver: &ctx.accounts.receiver,
+        token_mint: &ctx.accounts.token_mint,
+        receiver_token_account: &ctx.accounts.receiver_token_account,
+        bridge_authority: &ctx.accounts.bridge_authority,
+        fund_pool: &ctx.accounts.fund_pool,
+        pool_account: &mut ctx.accounts.pool_account,
+        token_relation: &ctx.accounts.token_relation,
+        fee_token_relation: &ctx.accounts.fee_token_relation,
+        fee_token_mint: &ctx.accounts.fee_token_mint,
+        fee_fund_pool: &ctx.accounts.fee_fund_pool,
+        fee_pool_account: &mut ctx.accounts.fee_pool_account,
+        gas_fee_token_account: &ctx.accounts.verifier_fee_token_account,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
+    if !release.has_liquidity(&msg_header, &msg_body)? {
+        msg!("insufficient pool liquidity, execution deferred");
+        return bridge_core::cpi::fail_message(cpi_ctx);
+    }
+    bridge_core::cpi::execute_message(cpi_ctx)?;
+    release.release(&msg_header, &msg_body, signer_seeds)
+}
+
+/// Quote the lamports and tokens bridge_proposal pulls from the sender,
+/// returned through the return data
+pub fn quote_bridge(
+    ctx: Context<QuoteBridge>,
+    to_chain: Chain,
+    _to_token: [u8; 32],
+    all_amount: u64,
+    upload_gas_fee: u64,
+) -> Result<BridgeQuote> {
+    let cpi_program = ctx.accounts.bridge_core_program.to_account_info();
+    let cpi_accounts = bridge_core::cpi::accounts::QuoteSend {
+        bridge_config: ctx.accounts.bridge_config.to_account_info(),
+        chain_fee: ctx.accounts.chain_fee.to_account_info(),
+        app_registration: ctx.accounts.app_registration.to_account_info(),
+        caller_program: ctx.accounts.program_id.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    let bridge_fee = bridge_core::cpi::quote_send(cpi_ctx, to_chain, MsgBody::EVM_LEN as u32)?
+        .get()
+        .bridge_fee;
+
+    let lamports = bridge_fee
+        .checked_add(upload_gas_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(BridgeQuote {
+        bridge_fee,
+        upload_gas_fee,
+        lamports,
+        all_amount,
+    })
+}
+
+/// Quote what bridge_finish pays out for a message, returned through the return data
+pub fn quote_finish(
+    ctx: Context<QuoteFinish>,
+    _from_chain: Chain,
+    _source_token: [u8; 32],
+    all_amount: u128,
+    upload_gas_fee: u128,
+) -> Result<FinishQuote> {
+    let amount = ctx.accounts.token_relation.to_local_amount(all_amount)?;
+    let lp_fee = if ctx.accounts.token_relation.mint_type == MintType::Mint as u8 {
+        0
     } else {
-        let from_decimals = ctx.accounts.token_relation.from_decimals;
-        let to_decimals = ctx.accounts.token_relation.to_decimals;
-        let all_amount =
-            msg_body.all_amount * 10u128.pow(to_decimals.into()) / 10u128.pow(from_decimals.into());
-
-        let lp_fee = all_amount * ctx.accounts.pool_account.pool_fee_rate as u128 / 1000000;
-        let final_amount = all_amount - lp_fee;
-        msg!("transfer token==> {} lp_fee {}", final_amount, lp_fee);
-        // calc fee to lp provider
-        let balance = ctx.accounts.fund_pool.amount;
-        ctx.accounts
-            .pool_account
-            .refresh_rewards(balance, lp_fee as u64, all_amount as u64)?;
-
-        // transfer token
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.fund_pool.to_account_info(),
-                to: ctx.accounts.receiver_token_account.to_account_info(),
-                authority: ctx.accounts.bridge_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, final_amount as u64)?;
-        if ctx.accounts.token_mint.key() == spl_token::native_mint::ID {
-            // wsol to sol
-            let cpi_ctx = CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                anchor_spl::token::CloseAccount {
-                    account: ctx.accounts.receiver_token_account.to_account_info(),
-                    destination: ctx.accounts.sender.to_account_info(),
-                    authority: ctx.accounts.sender.to_account_info(),
+        ctx.accounts.pool_account.lp_fee(amount)?
+    };
+    let gas_fee = ctx.accounts.fee_token_relation.to_local_amount(upload_gas_fee)?;
+
+    let received_amount = amount
+        .checked_sub(lp_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let to_u64 = |value: u128| u64::try_from(value).map_err(|_| ProgramError::ArithmeticOverflow);
+
+    Ok(FinishQuote {
+        amount: to_u64(amount)?,
+        lp_fee: to_u64(lp_fee)?,
+        received_amount: to_u64(received_amount)?,
+        gas_fee: to_u64(gas_fee)?,
+    })
+}
+
+/// Accounts paying out an inbound transfer, shared by bridge_finish and bridge_execute
+struct Release<'a, 'info> {
+    sender: &'a Signer<'info>,
+    receiver: &'a AccountInfo<'info>,
+    token_mint: &'a Account<'info, Mint>,
+    receiver_token_account: &'a Account<'info, TokenAccount>,
+    bridge_authority: &'a AccountInfo<'info>,
+    fund_pool: &'a Account<'info, TokenAccount>,
+    pool_account: &'a mut Account<'info, Pool>,
+    token_relation: &'a Account<'info, TokenRelation>,
+    fee_token_relation: &'a Account<'info, TokenRelation>,
+    fee_token_mint: &'a Account<'info, Mint>,
+    fee_fund_pool: &'a Account<'info, TokenAccount>,
+    fee_pool_account: &'a mut Account<'info, Pool>,
+    // receives the upload gas fee, owned by the relayer that verified the message
+    gas_fee_token_account: &'a Account<'info, TokenAccount>,
+    token_program: &'a Program<'info, Token>,
+    system_program: &'a Program<'info, System>,
+}
+
+impl<'a, 'info> Release<'a, 'info> {
+    /// Whether the pools hold enough to pay the transfer and the gas fee
+    fn has_liquidity(&self, msg_header: &MsgHeader, msg_body: &MsgBody) -> Result<bool> {
+        let mut token_needed = 0u128;
+        if self.token_relation.mint_type != MintType::Mint as u8 {
+            let all_amount = self.token_relation.to_local_amount(msg_body.all_amount)?;
+            token_needed = all_amount - self.pool_account.lp_fee(all_amount)?;
+        }
+        let mut fee_needed = 0u128;
+        if self.fee_token_relation.mint_type != MintType::Mint as u8 {
+            fee_needed = self.fee_token_relation.to_local_amount(msg_header.upload_gas_fee)?;
+        }
+        if self.fund_pool.key() == self.fee_fund_pool.key() {
+            let needed = token_needed
+                .checked_add(fee_needed)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            return Ok(self.fund_pool.amount as u128 >= needed);
+        }
+        Ok(self.fund_pool.amount as u128 >= token_needed && self.fee_fund_pool.amount as u128 >= fee_needed)
+    }
+
+    fn release(&mut self, msg_header: &MsgHeader, msg_body: &MsgBody, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        // decode the body
+        if self.token_relation.mint_type == MintType::Mint as u8 {
+            let all_amount = self.token_relation.to_local_amount(msg_body.all_amount)?;
+            msg!("mint token==> {}", all_amount);
+            // mint token
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.token_mint.to_account_info(),
+                    to: self.receiver_token_account.to_account_info(),
+                    authority: self.bridge_authority.to_account_info(),
                 },
+                signer_seeds,
             );
-            token::close_account(cpi_ctx)?;
-
-            // transfer sol to receiver
-            invoke(
-                &system_instruction::transfer(
-                    ctx.accounts.sender.key,
-                    &ctx.accounts.receiver.key(),
-                    final_amount as u64,
-                ),
-                &[
-                    ctx.accounts.sender.to_account_info(),
-                    ctx.accounts.receiver.to_account_info(),
-                    ctx.accounts.system_program.to_account_info(),
-                ],
-            )?;
+            token::mint_to(cpi_ctx, all_amount as u64)?;
+        } else {
+            let all_amount = self.token_relation.to_local_amount(msg_body.all_amount)?;
+
+            let lp_fee = self.pool_account.lp_fee(all_amount)?;
+            let final_amount = all_amount - lp_fee;
+            msg!("transfer token==> {} lp_fee {}", final_amount, lp_fee);
+            // calc fee to lp provider
+            let balance = self.fund_pool.amount;
+            self.pool_account
+                .refresh_rewards(balance, lp_fee as u64, all_amount as u64)?;
+
+            // transfer token
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: self.fund_pool.to_account_info(),
+                    to: self.receiver_token_account.to_account_info(),
+                    authority: self.bridge_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, final_amount as u64)?;
+            if self.token_mint.key() == spl_token::native_mint::ID {
+                // wsol to sol
+                let cpi_ctx = CpiContext::new(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::CloseAccount {
+                        account: self.receiver_token_account.to_account_info(),
+                        destination: self.sender.to_account_info(),
+                        authority: self.sender.to_account_info(),
+                    },
+                );
+                token::close_account(cpi_ctx)?;
+
+                // transfer sol to receiver
+                invoke(
+                    &system_instruction::transfer(
+                        self.sender.key,
+                        &self.receiver.key(),
+                        final_amount as u64,
+                    ),
+                    &[
+                        self.sender.to_account_info(),
+                        self.receiver.to_account_info(),
+                        self.system_program.to_account_info(),
+                    ],
+                )?;
+            }
         }
-    }
 
-    // mint or transfer gas fee to sender
-    if ctx.accounts.fee_token_relation.mint_type == MintType::Mint as u8 {
-        let from_decimals = ctx.accounts.fee_token_relation.from_decimals;
-        let to_decimals = ctx.accounts.fee_token_relation.to_decimals;
-        let gas_fee = msg_header
-            .upload_gas_fee
-            .checked_mul(10u128.pow(to_decimals.into()))
-            .and_then(|v| v.checked_div(10u128.pow(from_decimals.into())))
-            .expect("overflow in decimals conversion");
-        // mint token
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.fee_token_mint.to_account_info(),
-                to: ctx.accounts.sender_fee_token_account.to_account_info(),
-                authority: ctx.accounts.bridge_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::mint_to(cpi_ctx, gas_fee as u64)?;
-    } else {
-        let from_decimals = ctx.accounts.fee_token_relation.from_decimals;
-        let to_decimals = ctx.accounts.fee_token_relation.to_decimals;
-        let gas_fee = msg_header.upload_gas_fee * 10u128.pow(to_decimals.into())
-            / 10u128.pow(from_decimals.into());
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.fee_fund_pool.to_account_info(),
-                to: ctx.accounts.sender_fee_token_account.to_account_info(),
-                authority: ctx.accounts.bridge_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, gas_fee as u64)?;
+        // mint or transfer gas fee to the relayer
+        let gas_fee = self.fee_token_relation.to_local_amount(msg_header.upload_gas_fee)?;
+        if self.fee_token_relation.mint_type == MintType::Mint as u8 {
+            // mint token
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.fee_token_mint.to_account_info(),
+                    to: self.gas_fee_token_account.to_account_info(),
+                    authority: self.bridge_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::mint_to(cpi_ctx, gas_fee as u64)?;
+        } else {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: self.fee_fund_pool.to_account_info(),
+                    to: self.gas_fee_token_account.to_account_info(),
+                    authority: self.bridge_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, gas_fee as u64)?;
 
-        // transfer gas fee from pool
-        ctx.accounts
-            .fee_pool_account
-            .transfer_from_pool(gas_fee as i64)?;
-    }
+            // transfer gas fee from pool
+            self.fee_pool_account.transfer_from_pool(gas_fee as i64)?;
+        }
 
-    Ok(())
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -299,14 +464,29 @@ pub struct Proposal<'info> {
     #[account(seeds = [&to_chain.combain_chain(), CHAIN_RELATION_SEED.as_bytes()], bump)]
     pub chain_relation: Account<'info, ChainRelation>,
     /// CHECK:
-    #[account(mut, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    #[account(mut, seeds = [&to_chain.combain_chain(), ToChainNonce::SEED_SUFFIX.as_bytes(), crate::ID.as_ref(), &chain_relation.from_excutor], bump, seeds::program = bridge_core_program.key())]
     pub to_chain_nonce_account: AccountInfo<'info>,
     /// CHECK:
+    #[account(mut, seeds = [&to_chain.combain_chain(), OutboundRing::SEED_SUFFIX.as_bytes(), crate::ID.as_ref(), &chain_relation.from_excutor], bump, seeds::program = bridge_core_program.key())]
+    pub outbound_ring: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut, seeds = [&to_chain.combain_chain(), OutboundTree::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub outbound_tree: AccountInfo<'info>,
+    /// CHECK:
     #[account(mut, seeds = [MESSAGE_FEE_SEED.as_bytes()], bump, seeds::program = bridge_core_program.key())]
     pub message_fee: AccountInfo<'info>,
     /// CHECK:
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump, seeds::program = bridge_core_program.key())]
     pub bridge_config: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [&to_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub chain_pause: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [&to_chain.combain_chain(), ChainFee::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub chain_fee: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [crate::ID.as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub app_registration: AccountInfo<'info>,
     pub bridge_core_program: Program<'info, bridge_core::program::BridgeCore>,
     /// CHECK:
     #[account(address = crate::ID)]
@@ -316,6 +496,39 @@ pub struct Proposal<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(to_chain: Chain, to_token: [u8; 32])]
+pub struct QuoteBridge<'info> {
+    #[account(seeds = [&to_chain.combain_chain(), &to_token], bump)]
+    pub token_relation: Account<'info, TokenRelation>,
+    /// CHECK:
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub bridge_config: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [&to_chain.combain_chain(), ChainFee::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub chain_fee: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [crate::ID.as_ref(), AppRegistration::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub app_registration: AccountInfo<'info>,
+    pub bridge_core_program: Program<'info, bridge_core::program::BridgeCore>,
+    /// CHECK:
+    #[account(address = crate::ID)]
+    pub program_id: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(from_chain: Chain, source_token: [u8; 32])]
+pub struct QuoteFinish<'info> {
+    #[account(seeds = [&from_chain.combain_chain(), &source_token], bump)]
+    pub token_relation: Account<'info, TokenRelation>,
+    #[account(seeds = [token_relation.to_token.as_ref(), Pool::SEEDS.as_bytes()], bump)]
+    pub pool_account: Account<'info, Pool>,
+    #[account(seeds = [&from_chain.combain_chain(), CHAIN_RELATION_SEED.as_bytes()], bump)]
+    pub chain_relation: Account<'info, ChainRelation>,
+    #[account(seeds = [&from_chain.combain_chain(), &chain_relation.fee_token], bump)]
+    pub fee_token_relation: Account<'info, TokenRelation>,
+}
+
 #[derive(Accounts)]
 #[instruction(msg_header: MsgHeader, msg_body: MsgBody)]
 pub struct Consumption<'info> {
@@ -355,11 +568,157 @@ pub struct Consumption<'info> {
     #[account(mut)]
     pub from_chain_nonce_account: AccountInfo<'info>,
     /// CHECK:
+    #[account(mut)]
+    pub message_receipt: AccountInfo<'info>,
+    /// CHECK:
     #[account(mut, seeds = [MESSAGE_FEE_SEED.as_bytes()], bump, seeds::program = bridge_core_program.key())]
     pub message_fee: AccountInfo<'info>,
     /// CHECK:
     #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump, seeds::program = bridge_core_program.key())]
     pub bridge_config: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub chain_pause: AccountInfo<'info>,
+    /// CHECK:
+    pub validator_set: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub fee_split: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub treasury_balance: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub relayer_balance: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub validator_rewards: AccountInfo<'info>,
+    /// CHECK:
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    pub bridge_core_program: Program<'info, bridge_core::program::BridgeCore>,
+    /// CHECK:
+    #[account(address = crate::ID)]
+    pub program_id: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Consumption<'info> {
+    /// Only transfers of the bridge-token program of the source chain to this program
+    fn check_message(&self, msg_header: &MsgHeader) -> Result<()> {
+        require!(
+            msg_header.from_addr == self.chain_relation.from_excutor,
+            crate::error::ErrorCode::SenderAddrNotMatch
+        );
+
+        require!(
+            msg_header.to_addr == crate::ID.to_bytes(),
+            crate::error::ErrorCode::ExecuteAddrNotMatch
+        );
+        Ok(())
+    }
+
+    /// The bridge-core accounts confirming the message
+    fn confirm_accounts(&self) -> bridge_core::cpi::accounts::ConfirmFromOtherChain<'info> {
+        bridge_core::cpi::accounts::ConfirmFromOtherChain {
+            from_chain_nonce_account: self.from_chain_nonce_account.to_account_info(),
+            message_receipt: self.message_receipt.to_account_info(),
+            bridge_config: self.bridge_config.to_account_info(),
+            chain_pause: self.chain_pause.to_account_info(),
+            validator_set: self.validator_set.to_account_info(),
+            fee_split: self.fee_split.to_account_info(),
+            message_fee: self.message_fee.to_account_info(),
+            treasury_balance: self.treasury_balance.to_account_info(),
+            relayer_balance: self.relayer_balance.to_account_info(),
+            validator_rewards: self.validator_rewards.to_account_info(),
+            user: self.sender.to_account_info(),
+            receiver: self.receiver.to_account_info(),
+            caller_auth_pda: self.bridge_authority.to_account_info(),
+            caller_program: self.program_id.to_account_info(),
+            instructions: self.instructions.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+
+    fn release(&mut self, msg_header: &MsgHeader, msg_body: &MsgBody, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut release = Release {
+            sender: &self.sender,
+            receiver: &self.receiver,
+            token_mint: &self.token_mint,
+            receiver_token_account: &self.receiver_token_account,
+            bridge_authority: &self.bridge_authority,
+            fund_pool: &self.fund_pool,
+            pool_account: &mut self.pool_account,
+            token_relation: &self.token_relation,
+            fee_token_relation: &self.fee_token_relation,
+            fee_token_mint: &self.fee_token_mint,
+            fee_fund_pool: &self.fee_fund_pool,
+            fee_pool_account: &mut self.fee_pool_account,
+            gas_fee_token_account: &self.sender_fee_token_account,
+            token_program: &self.token_program,
+            system_program: &self.system_program,
+        };
+        release.release(msg_header, msg_body, signer_seeds)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader, msg_body: MsgBody)]
+pub struct ProofConsumption<'info> {
+    pub consumption: Consumption<'info>,
+    /// CHECK: the RootRecord the proof is checked against
+    #[account(owner = consumption.bridge_core_program.key())]
+    pub root_record: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(msg_header: MsgHeader, msg_body: MsgBody)]
+pub struct Execution<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(mut)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    /// CHECK:
+    #[account(mut, address = Pubkey::new_from_array(msg_body.to_who))]
+    pub receiver: AccountInfo<'info>,
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = if token_mint.key() == spl_token::native_mint::ID { sender.to_account_info() } else { receiver.to_account_info() })]
+    pub receiver_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK:
+    #[account(seeds = [BRIDGE_SEED.as_ref()], bump)]
+    pub bridge_authority: AccountInfo<'info>,
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = bridge_authority)]
+    pub fund_pool: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [token_mint.key().as_ref(), Pool::SEEDS.as_bytes()], bump)]
+    pub pool_account: Box<Account<'info, Pool>>,
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), &msg_body.source_token], bump)]
+    pub token_relation: Box<Account<'info, TokenRelation>>,
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), CHAIN_RELATION_SEED.as_bytes()], bump)]
+    pub chain_relation: Box<Account<'info, ChainRelation>>,
+
+    #[account(seeds = [&&msg_header.from_chain.combain_chain(), &chain_relation.fee_token], bump)]
+    pub fee_token_relation: Box<Account<'info, TokenRelation>>,
+    #[account(mut, address = fee_token_relation.to_token)]
+    pub fee_token_mint: Box<Account<'info, Mint>>,
+    #[account(mut, associated_token::mint = fee_token_mint, associated_token::authority = bridge_authority)]
+    pub fee_fund_pool: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [fee_token_mint.key().as_ref(), Pool::SEEDS.as_bytes()], bump)]
+    pub fee_pool_account: Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds = [&message_hash(&msg_header, &msg_body.to_evm_buffer()), PendingMessage::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub pending_message: Box<Account<'info, PendingMessage>>,
+    /// CHECK: the relayer that verified the message, paid the gas fee and the pending message rent
+    #[account(mut, address = pending_message.verifier)]
+    pub verifier: AccountInfo<'info>,
+    #[account(mut, associated_token::mint = fee_token_mint, associated_token::authority = verifier)]
+    pub verifier_fee_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK:
+    #[account(seeds = [ConfigInfo::SEEDS.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub bridge_config: AccountInfo<'info>,
+    /// CHECK:
+    #[account(seeds = [&msg_header.from_chain.combain_chain(), ChainPause::SEED_SUFFIX.as_bytes()], bump, seeds::program = bridge_core_program.key())]
+    pub chain_pause: AccountInfo<'info>,
     pub bridge_core_program: Program<'info, bridge_core::program::BridgeCore>,
     /// CHECK:
     #[account(address = crate::ID)]
@@ -1,4 +1,4 @@
-use crate::state::executor::MsgBody;
+use crate::state::executor::{BridgeQuote, FinishQuote, MsgBody};
 use anchor_lang::prelude::*;
 use bridge_core::state::config::Chain;
 use bridge_core::state::config::MsgHeader;
@@ -11,6 +11,7 @@ use instructions::{
 };
 
 mod error;
+mod event;
 mod instructions;
 pub mod state;
 
@@ -26,8 +27,18 @@ pub mod bridge_token {
         Ok(())
     }
 
-    pub fn change_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
-        config::change_admin(ctx, new_admin)?;
+    pub fn propose_admin(ctx: Context<BridgeConf>, new_admin: Pubkey) -> Result<()> {
+        config::propose_admin(ctx, new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        config::accept_admin(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_admin_proposal(ctx: Context<BridgeConf>) -> Result<()> {
+        config::cancel_admin_proposal(ctx)?;
         Ok(())
     }
 
@@ -61,6 +72,11 @@ pub mod bridge_token {
         Ok(())
     }
 
+    pub fn init_channel(ctx: Context<InitChannel>, from_chain: Chain) -> Result<()> {
+        config::init_channel(ctx, from_chain)?;
+        Ok(())
+    }
+
     pub fn token_new(
         ctx: Context<TokenNew>,
         decimals: u8,
@@ -109,6 +125,28 @@ pub mod bridge_token {
         Ok(())
     }
 
+    pub fn quote_bridge(
+        ctx: Context<QuoteBridge>,
+        to_chain: Chain,
+        to_token: [u8; 32],
+        all_amount: u64,
+        upload_gas_fee: u64,
+    ) -> Result<BridgeQuote> {
+        let quote = executor::quote_bridge(ctx, to_chain, to_token, all_amount, upload_gas_fee)?;
+        Ok(quote)
+    }
+
+    pub fn quote_finish(
+        ctx: Context<QuoteFinish>,
+        from_chain: Chain,
+        source_token: [u8; 32],
+        all_amount: u128,
+        upload_gas_fee: u128,
+    ) -> Result<FinishQuote> {
+        let quote = executor::quote_finish(ctx, from_chain, source_token, all_amount, upload_gas_fee)?;
+        Ok(quote)
+    }
+
     pub fn bridge_finish(
         ctx: Context<Consumption>,
         msg_header: MsgHeader,
@@ -119,4 +157,24 @@ pub mod bridge_token {
         executor::bridge_finish(ctx, msg_header, msg_body, accum_pk, signatures)?;
         Ok(())
     }
+
+    pub fn bridge_finish_with_proof(
+        ctx: Context<ProofConsumption>,
+        msg_header: MsgHeader,
+        msg_body: MsgBody,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        executor::bridge_finish_with_proof(ctx, msg_header, msg_body, leaf_index, proof)?;
+        Ok(())
+    }
+
+    pub fn bridge_execute(
+        ctx: Context<Execution>,
+        msg_header: MsgHeader,
+        msg_body: MsgBody,
+    ) -> Result<()> {
+        executor::bridge_execute(ctx, msg_header, msg_body)?;
+        Ok(())
+    }
 }
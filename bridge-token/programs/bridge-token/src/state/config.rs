@@ -5,7 +5,7 @@ use bridge_core::state::config::Chain;
 pub struct ConfigInfo {
     pub admin: Pubkey,
     pub bump: u8,
-    pub padding: [u8; 32],
+    pub pending_admin: Pubkey,
 }
 impl ConfigInfo {
     pub const LEN: usize = 32 + 1 + 32;
@@ -23,6 +23,20 @@ pub struct TokenRelation {
 }
 impl TokenRelation {
     pub const LEN: usize = Chain::LEN + 32 + 1 + 32 + 1 + 1;
+
+    /// Convert an amount of `from_token` into `to_token` decimals
+    pub fn to_local_amount(&self, amount: u128) -> Result<u128> {
+        let to_scale = 10u128
+            .checked_pow(self.to_decimals.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let from_scale = 10u128
+            .checked_pow(self.from_decimals.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(amount
+            .checked_mul(to_scale)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / from_scale)
+    }
 }
 
 #[account]
@@ -8,6 +8,8 @@ pub struct MsgBody {
     pub to_who: [u8; 32]
 }
 impl MsgBody {
+    pub const EVM_LEN: usize = 32 + 16 + 32 + 32;
+
     pub fn to_evm_buffer(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
         buffer.extend_from_slice(&self.source_token);
@@ -51,3 +53,24 @@ impl MsgBody {
         }
     }
 }
+
+/// Returned by quote_bridge, the cost of a bridge_proposal
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub struct BridgeQuote {
+    pub bridge_fee: u64,
+    pub upload_gas_fee: u64,
+    // lamports pulled from the sender, bridge_fee + upload_gas_fee
+    pub lamports: u64,
+    pub all_amount: u64,
+}
+
+/// Returned by quote_finish, what bridge_finish pays out for a message
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub struct FinishQuote {
+    // all_amount in local decimals
+    pub amount: u64,
+    pub lp_fee: u64,
+    pub received_amount: u64,
+    // upload gas fee paid to the relayer, in local decimals of the fee token
+    pub gas_fee: u64,
+}
@@ -35,6 +35,13 @@ pub struct Pool {
 impl Pool {
     pub const LEN: usize = 32 + 8 * 8 + 32;
     pub const SEEDS: &str = super::POOL_SEED;
+    /// Fee kept by the liquidity providers on a transfer out of the pool
+    pub fn lp_fee(&self, amount: u128) -> Result<u128> {
+        Ok(amount
+            .checked_mul(self.pool_fee_rate as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / 1000000)
+    }
     #[inline(never)]
     pub fn transfer_to_pool(&mut self, amount: i64) -> Result<()> {
         self.total_liquidity += amount as u64;
@@ -5,6 +5,7 @@ import { base64, bs58 } from "@coral-xyz/anchor/dist/cjs/utils/bytes";
 import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccountIdempotentInstruction, createCloseAccountInstruction, createSyncNativeInstruction, getAssociatedTokenAddress, NATIVE_MINT, TOKEN_PROGRAM_ID, } from "@solana/spl-token"
 import { PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
 import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import { expect } from "chai";
 
 describe("bridge-token", () => {
   // Configure the client to use the local cluster.
@@ -95,11 +96,36 @@ describe("bridge-token", () => {
     );
     console.log("chain_relation ", chain_relation.toBase58());
     //msg 合约
+    // the outbound channel from bridge-token to the executor of the destination chain
+    const chainRelationInfo = await program.account.chainRelation.fetch(chain_relation);
     let [to_chain_nonce_account] = PublicKey.findProgramAddressSync(
-      [combain_chain(to_chain), Buffer.from(anchor.utils.bytes.utf8.encode("toNonce"))],
+      [
+        combain_chain(to_chain),
+        Buffer.from(anchor.utils.bytes.utf8.encode("toNonce")),
+        bridge_program_id.toBuffer(),
+        Buffer.from(chainRelationInfo.fromExcutor),
+      ],
       msg_program_id
     );
     console.log("to_chain_nonce_account ", to_chain_nonce_account.toBase58());
+    let [outbound_ring] = PublicKey.findProgramAddressSync(
+      [
+        combain_chain(to_chain),
+        Buffer.from(anchor.utils.bytes.utf8.encode("outbound")),
+        bridge_program_id.toBuffer(),
+        Buffer.from(chainRelationInfo.fromExcutor),
+      ],
+      msg_program_id
+    );
+    console.log("outbound_ring ", outbound_ring.toBase58());
+    let [outbound_tree] = PublicKey.findProgramAddressSync(
+      [
+        combain_chain(to_chain),
+        Buffer.from(anchor.utils.bytes.utf8.encode("outboundTree")),
+      ],
+      msg_program_id
+    );
+    console.log("outbound_tree ", outbound_tree.toBase58());
     let [message_fee] = PublicKey.findProgramAddressSync(
       [Buffer.from(anchor.utils.bytes.utf8.encode("vaultFee"))],
       msg_program_id
@@ -110,6 +136,21 @@ describe("bridge-token", () => {
       msg_program_id
     );
     console.log("bridge_config ", bridge_config.toBase58());
+    let [chain_pause] = PublicKey.findProgramAddressSync(
+      [combain_chain(to_chain), Buffer.from(anchor.utils.bytes.utf8.encode("chainPause"))],
+      msg_program_id
+    );
+    console.log("chain_pause ", chain_pause.toBase58());
+    let [chain_fee] = PublicKey.findProgramAddressSync(
+      [combain_chain(to_chain), Buffer.from(anchor.utils.bytes.utf8.encode("chainFee"))],
+      msg_program_id
+    );
+    console.log("chain_fee ", chain_fee.toBase58());
+    let [app_registration] = PublicKey.findProgramAddressSync(
+      [bridge_program_id.toBuffer(), Buffer.from(anchor.utils.bytes.utf8.encode("app"))],
+      msg_program_id
+    );
+    console.log("app_registration ", app_registration.toBase58());
 
     let toToken: number[] = new Array(32).fill(0);
     toToken = to_token.toBytes() as any;
@@ -130,8 +171,13 @@ describe("bridge-token", () => {
         tokenRelation: token_relation,
         chainRelation: chain_relation,
         toChainNonceAccount: to_chain_nonce_account,
+        outboundRing: outbound_ring,
+        outboundTree: outbound_tree,
         messageFee: message_fee,
         bridgeConfig: bridge_config,
+        chainPause: chain_pause,
+        chainFee: chain_fee,
+        appRegistration: app_registration,
         bridgeCoreProgram: msg_program_id,
         programId: bridge_program_id,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -153,8 +199,13 @@ describe("bridge-token", () => {
         tokenRelation: token_relation,
         chainRelation: chain_relation,
         toChainNonceAccount: to_chain_nonce_account,
+        outboundRing: outbound_ring,
+        outboundTree: outbound_tree,
         messageFee: message_fee,
         bridgeConfig: bridge_config,
+        chainPause: chain_pause,
+        chainFee: chain_fee,
+        appRegistration: app_registration,
         bridgeCoreProgram: msg_program_id,
         programId: bridge_program_id,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -172,6 +223,52 @@ describe("bridge-token", () => {
     transactions.feePayer = sender;
     let result = await provider.connection.simulateTransaction(transactions);
     console.log(result);
+
+    // the lamports and tokens the proposal above pulls, from the return data
+    const quote = await program.methods.quoteBridge({
+      chainType: to_chain.chainType,
+      chainId: to_chain.chainId
+    }, toToken, all_amount, upload_gas_fee)
+      .accounts({
+        tokenRelation: token_relation,
+        bridgeConfig: bridge_config,
+        chainFee: chain_fee,
+        appRegistration: app_registration,
+        bridgeCoreProgram: msg_program_id,
+        programId: bridge_program_id,
+      }).view();
+    console.log("quote==>", quote);
+    expect(quote.allAmount.eq(all_amount)).to.be.true;
+    expect(quote.uploadGasFee.eq(upload_gas_fee)).to.be.true;
+    expect(quote.lamports.eq(quote.bridgeFee.add(upload_gas_fee))).to.be.true;
+
+    // what bridge_finish pays out for the same transfer coming back, in local decimals
+    const tokenRelationInfo = await program.account.tokenRelation.fetch(token_relation);
+    let [fee_token_relation] = PublicKey.findProgramAddressSync(
+      [combain_chain(to_chain), Buffer.from(chainRelationInfo.feeToken)],
+      bridge_program_id
+    );
+    const feeTokenRelationInfo = await program.account.tokenRelation.fetch(fee_token_relation);
+    const finishQuote = await program.methods.quoteFinish({
+      chainType: to_chain.chainType,
+      chainId: to_chain.chainId
+    }, Array.from(to_token.toBytes()), all_amount, upload_gas_fee)
+      .accounts({
+        tokenRelation: token_relation,
+        poolAccount: PublicKey.findProgramAddressSync(
+          [tokenRelationInfo.toToken.toBuffer(), Buffer.from(anchor.utils.bytes.utf8.encode("pool"))],
+          bridge_program_id
+        )[0],
+        chainRelation: chain_relation,
+        feeTokenRelation: fee_token_relation,
+      }).view();
+    console.log("finish quote==>", finishQuote);
+    const toLocal = (amount: anchor.BN, relation: { fromDecimals: number; toDecimals: number }) =>
+      amount.mul(new anchor.BN(10).pow(new anchor.BN(relation.toDecimals)))
+        .div(new anchor.BN(10).pow(new anchor.BN(relation.fromDecimals)));
+    expect(finishQuote.amount.eq(toLocal(all_amount, tokenRelationInfo))).to.be.true;
+    expect(finishQuote.receivedAmount.eq(finishQuote.amount.sub(finishQuote.lpFee))).to.be.true;
+    expect(finishQuote.gasFee.eq(toLocal(upload_gas_fee, feeTokenRelationInfo))).to.be.true;
   });
 });
 